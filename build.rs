@@ -0,0 +1,191 @@
+#[path = "build/message_refs.rs"]
+mod message_refs;
+
+use fluent_syntax::ast;
+use message_refs::{collect_messages, line_column, resolve_variables};
+use std::{env, fmt::Write, fs, path::Path};
+
+const LOCALE_DIR: &str = "site/locale";
+/// Locale the typed messages are generated from.
+/// Must be the same as the fallback language used at runtime.
+const FALLBACK_LOCALE: &str = "en-GB";
+
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "static", "struct", "super", "trait", "true", "type", "unsafe", "use",
+    "where", "while",
+];
+
+/// Native name of the locale as declared by its `language-name` message.
+fn language_name(source: &str) -> Option<&str> {
+    source.lines().find_map(|line| {
+        let (id, value) = line.split_once('=')?;
+        if id.trim() == "language-name" {
+            Some(value.trim())
+        } else {
+            None
+        }
+    })
+}
+
+/// Generate the list of locales embedded into the binary.
+/// The list is empty unless the `embed-locales` feature is enabled.
+fn generate_embedded_locales() -> String {
+    let mut code = String::from("&[\n");
+
+    if env::var_os("CARGO_FEATURE_EMBED_LOCALES").is_some() {
+        let mut paths: Vec<_> = fs::read_dir(LOCALE_DIR)
+            .expect("failed to read locale directory")
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "ftl"))
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            println!("cargo:rerun-if-changed={}", path.display());
+
+            let id = path.file_stem().unwrap().to_str().unwrap();
+            let source = fs::read_to_string(&path).unwrap();
+            let name = language_name(&source).unwrap_or(id);
+            let abs_path = fs::canonicalize(&path).unwrap();
+            writeln!(
+                code,
+                "    EmbeddedLocale {{ id: {:?}, name: {:?}, source: include_str!({:?}) }},",
+                id, name, abs_path
+            )
+            .unwrap();
+        }
+    }
+
+    code.push(']');
+    code
+}
+
+/// Short textual representation of a pattern used for documentation.
+fn describe_pattern(pattern: &ast::Pattern) -> String {
+    pattern
+        .elements
+        .iter()
+        .map(|element| match element {
+            ast::PatternElement::TextElement(text) => (*text).to_owned(),
+            ast::PatternElement::Placeable(ast::Expression::InlineExpression(
+                ast::InlineExpression::VariableReference { id },
+            )) => format!("{{ ${} }}", id.name),
+            ast::PatternElement::Placeable(_) => String::from("{ … }"),
+        })
+        .collect()
+}
+
+fn rust_ident(name: &str) -> String {
+    let mut ident = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c == '-' {
+            ident.push('_');
+        } else if c.is_ascii_uppercase() {
+            if i > 0 && !ident.ends_with('_') {
+                ident.push('_');
+            }
+            ident.push(c.to_ascii_lowercase());
+        } else {
+            ident.push(c);
+        }
+    }
+
+    if RUST_KEYWORDS.contains(&ident.as_str()) {
+        format!("r#{}", ident)
+    } else {
+        ident
+    }
+}
+
+/// Generate typed accessors for all messages of the fallback locale.
+fn generate_messages() -> String {
+    let path = Path::new(LOCALE_DIR).join(format!("{}.ftl", FALLBACK_LOCALE));
+    println!("cargo:rerun-if-changed={}", path.display());
+
+    let source = fs::read_to_string(&path).expect("failed to read fallback locale");
+    let resource = fluent_syntax::parser::parse(&source).unwrap_or_else(|(_, errors)| {
+        let errors: Vec<_> = errors
+            .iter()
+            .map(|err| {
+                let (line, column) = line_column(&source, err.pos.0);
+                format!("{}:{}:{}: {:?}", path.display(), line, column, err.kind)
+            })
+            .collect();
+        panic!("failed to parse {}:\n{}", path.display(), errors.join("\n"));
+    });
+
+    let messages: Vec<_> = resource
+        .body
+        .iter()
+        .filter_map(|entry| match entry {
+            ast::ResourceEntry::Entry(ast::Entry::Message(msg)) => Some(msg),
+            _ => None,
+        })
+        .collect();
+
+    let refs = collect_messages(&resource);
+
+    let mut code = String::new();
+    for msg in &messages {
+        let value = if let Some(value) = &msg.value {
+            value
+        } else {
+            continue;
+        };
+
+        let id = msg.id.name;
+        let variables = resolve_variables(id, &refs);
+
+        writeln!(code, "#[doc = {:?}]", describe_pattern(value)).unwrap();
+        if variables.is_empty() {
+            let name = rust_ident(id).trim_start_matches("r#").to_uppercase();
+            writeln!(
+                code,
+                "pub const {}: Message<'static> = Message::new({:?});\n",
+                name, id
+            )
+            .unwrap();
+        } else {
+            let params: Vec<_> = variables
+                .iter()
+                .map(|var| format!("{}: impl Into<fluent::FluentValue<'a>>", rust_ident(var)))
+                .collect();
+            writeln!(
+                code,
+                "pub fn {}<'a>({}) -> Message<'a> {{",
+                rust_ident(id),
+                params.join(", ")
+            )
+            .unwrap();
+            writeln!(code, "    let mut args = fluent::FluentArgs::new();").unwrap();
+            for var in &variables {
+                writeln!(
+                    code,
+                    "    args.insert({:?}, {}.into());",
+                    var,
+                    rust_ident(var)
+                )
+                .unwrap();
+            }
+            writeln!(code, "    Message::with_args({:?}, args)\n}}\n", id).unwrap();
+        }
+    }
+
+    code
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", LOCALE_DIR);
+    println!("cargo:rerun-if-changed=build/message_refs.rs");
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+    let out_dir = Path::new(&out_dir);
+    fs::write(
+        out_dir.join("embedded_locales.rs"),
+        generate_embedded_locales(),
+    )
+    .unwrap();
+    fs::write(out_dir.join("messages.rs"), generate_messages()).unwrap();
+}
@@ -0,0 +1,115 @@
+//! Analysis of the references between Fluent messages.
+//!
+//! Shared by the build script, which generates the typed messages, and the locale tests.
+
+use fluent_syntax::ast;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Variables and message references used by a message.
+#[derive(Default)]
+pub struct MessageRefs<'a> {
+    pub variables: Vec<&'a str>,
+    pub messages: Vec<&'a str>,
+}
+
+impl<'a> MessageRefs<'a> {
+    pub fn collect_pattern(&mut self, pattern: &ast::Pattern<'a>) {
+        for element in &pattern.elements {
+            if let ast::PatternElement::Placeable(expr) = element {
+                self.collect_expression(expr);
+            }
+        }
+    }
+
+    fn collect_expression(&mut self, expr: &ast::Expression<'a>) {
+        match expr {
+            ast::Expression::InlineExpression(expr) => self.collect_inline_expression(expr),
+            ast::Expression::SelectExpression { selector, variants } => {
+                self.collect_inline_expression(selector);
+                for variant in variants {
+                    self.collect_pattern(&variant.value);
+                }
+            }
+        }
+    }
+
+    fn collect_inline_expression(&mut self, expr: &ast::InlineExpression<'a>) {
+        match expr {
+            ast::InlineExpression::VariableReference { id }
+                if !self.variables.contains(&id.name) =>
+            {
+                self.variables.push(id.name);
+            }
+            ast::InlineExpression::MessageReference { id, .. } => self.messages.push(id.name),
+            ast::InlineExpression::FunctionReference {
+                arguments: Some(arguments),
+                ..
+            } => {
+                for arg in &arguments.positional {
+                    self.collect_inline_expression(arg);
+                }
+                for arg in &arguments.named {
+                    self.collect_inline_expression(&arg.value);
+                }
+            }
+            ast::InlineExpression::Placeable { expression } => self.collect_expression(expression),
+            _ => {}
+        }
+    }
+}
+
+/// Collect the references of every message in the resource.
+pub fn collect_messages<'a>(resource: &ast::Resource<'a>) -> BTreeMap<&'a str, MessageRefs<'a>> {
+    let mut refs = BTreeMap::new();
+    for entry in &resource.body {
+        if let ast::ResourceEntry::Entry(ast::Entry::Message(msg)) = entry {
+            let mut message_refs = MessageRefs::default();
+            if let Some(value) = &msg.value {
+                message_refs.collect_pattern(value);
+            }
+            refs.insert(msg.id.name, message_refs);
+        }
+    }
+    refs
+}
+
+/// Collect the variables used by a message including the ones used by referenced messages.
+/// Variables are in order of their first appearance.
+pub fn resolve_variables<'a>(
+    id: &'a str,
+    refs: &BTreeMap<&'a str, MessageRefs<'a>>,
+) -> Vec<&'a str> {
+    fn resolve<'a>(
+        id: &'a str,
+        refs: &BTreeMap<&'a str, MessageRefs<'a>>,
+        visited: &mut BTreeSet<&'a str>,
+        variables: &mut Vec<&'a str>,
+    ) {
+        if !visited.insert(id) {
+            return;
+        }
+
+        if let Some(message_refs) = refs.get(id) {
+            for var in &message_refs.variables {
+                if !variables.contains(var) {
+                    variables.push(var);
+                }
+            }
+            for referenced in &message_refs.messages {
+                resolve(referenced, refs, visited, variables);
+            }
+        }
+    }
+
+    let mut variables = Vec::new();
+    resolve(id, refs, &mut BTreeSet::new(), &mut variables);
+    variables
+}
+
+/// Line and column, both starting at 1, of the byte offset in the source.
+pub fn line_column(source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..offset.min(source.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.len() - before.rfind('\n').map_or(0, |i| i + 1) + 1;
+    (line, column)
+}
@@ -0,0 +1,25 @@
+// Generates the manifest of available locales (`site/locale/index.json`)
+// from the Fluent files in the locale directory.
+// The manifest maps each locale to its native name, taken from the `language-name` message.
+
+const fs = require("fs");
+const path = require("path");
+
+const LOCALE_DIR = path.join(__dirname, "..", "site", "locale");
+const NAME_PATTERN = /^language-name\s*=\s*(.+)$/m;
+
+const manifest = {};
+fs.readdirSync(LOCALE_DIR)
+  .filter((file) => path.extname(file) === ".ftl")
+  .sort()
+  .forEach((file) => {
+    const locale = path.basename(file, ".ftl");
+    const source = fs.readFileSync(path.join(LOCALE_DIR, file), "utf8");
+    const match = NAME_PATTERN.exec(source);
+    manifest[locale] = match ? match[1].trim() : locale;
+  });
+
+fs.writeFileSync(
+  path.join(LOCALE_DIR, "index.json"),
+  JSON.stringify(manifest, null, 2) + "\n"
+);
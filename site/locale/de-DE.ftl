@@ -0,0 +1,104 @@
+language-name = Deutsch
+
+title = Hyo
+
+create-game = Spiel erstellen
+join-game = Spiel beitreten
+continue-game = Spiel fortsetzen
+
+room-code = Raumcode
+join = Beitreten
+back = Zurück
+copy-invite-link = Einladungslink kopieren
+
+settings = Einstellungen
+language = Sprache
+language-browser-default = Browsereinstellung
+bidi-isolation = Namen vom umgebenden Text abgrenzen
+
+translation-overlay = Eigene Übersetzungen
+translation-overlay-description = Lade eine Fluent-Datei (.ftl), um die eingebauten Texte zu ersetzen.
+translation-overlay-active = Verwendet { $name }
+translation-overlay-file = Datei laden
+translation-overlay-url = Adresse einer .ftl-Datei
+translation-overlay-load = Laden
+translation-overlay-remove = Entfernen
+translation-overlay-error = Die Übersetzungen konnten nicht geladen werden.
+
+preferences = Spiel
+animation-speed = Animationsgeschwindigkeit
+animation-speed-slow = Langsam
+animation-speed-normal = Normal
+animation-speed-fast = Schnell
+reduced-motion = Animationen ausschalten
+auto-sort-hand = Meine Hand automatisch sortieren
+confirm-before-play = Karten zum Spielen zweimal anklicken
+playable-card-hints = Spielbare Karten hervorheben
+sound-volume = Lautstärke
+
+profile = Profil
+profile-name = Name
+profile-avatar = Avatar
+profile-avatar-more = Mehr
+profile-accent = Farbe
+player-default-name = Spieler
+
+stats = Statistik
+stats-games-played = Gespielte Spiele
+stats-games-won = Gewonnene Spiele
+stats-average-position = Durchschnittliche Platzierung
+stats-cards-drawn = Gezogene Karten
+stats-longest-streak = Längste Siegesserie
+stats-most-played-card = Meistgespielte Karte
+stats-missed-hyo-calls = Vergessene Hyo-Rufe
+stats-value = { NUMBER($value, maximumFractionDigits: 2) }
+stats-value-none = –
+stats-card = { CARD($card) }
+stats-history = Letzte Spiele
+stats-history-empty = Noch keine Spiele gespielt.
+stats-match-position = Platz { $position } von { $players }
+stats-match-cards-drawn = { $count ->
+        [one] eine Karte gezogen
+       *[other] { NUMBER($count) } Karten gezogen
+    }
+stats-export = Exportieren
+stats-import = Importieren
+stats-import-error = Die Datei enthält keine Statistik.
+
+call-hyo = Hyo!
+
+achievement-unlocked = Erfolg freigeschaltet: { $name }
+achievements = Erfolge
+achievement-first-win = Erster Sieg
+achievement-first-win-description = Gewinne ein Spiel.
+achievement-clean-hands = Saubere Hände
+achievement-clean-hands-description = Gewinne ein Spiel, ohne eine einzige Karte zu ziehen.
+achievement-comeback = Aufholjagd
+achievement-comeback-description = Gewinne ein Spiel, nachdem du 20 Karten auf der Hand hattest.
+achievement-lucky-sevens = Glückliche Sieben
+achievement-lucky-sevens-description = Spiele vier Siebenen in einem Spiel.
+achievement-loud-and-clear = Laut und deutlich
+achievement-loud-and-clear-description = Gewinne ein Spiel mit Hyo-Ruf, ohne ihn je zu vergessen.
+
+## Game events
+
+cards-drawn = { $name } hat { $count ->
+        [one] eine Karte
+       *[other] { NUMBER($count) } Karten
+    } gezogen
+card-played = { $name } hat { CARD($card) } gespielt
+players-ready = { LIST($players) } { $count ->
+        [one] ist
+       *[other] sind
+    } bereit
+
+## Used by the LIST(), NUMBER() and CARD() functions
+
+list-separator = {", "}
+list-and = {" und "}
+number-decimal-separator = ,
+number-group-separator = .
+card-color-red = Rot
+card-color-blue = Blau
+card-color-yellow = Gelb
+card-color-green = Grün
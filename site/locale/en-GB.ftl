@@ -1,4 +1,117 @@
+language-name = English
+
 title = Hyo
 
 create-game = Create Game
 join-game = Join Game
+continue-game = Continue Game
+
+room-code = Room Code
+join = Join
+back = Back
+copy-invite-link = Copy Invite Link
+
+settings = Settings
+language = Language
+language-browser-default = Browser default
+bidi-isolation = Keep names apart from the surrounding text
+
+translation-overlay = Custom Translations
+translation-overlay-description = Load a Fluent (.ftl) file to replace the built-in texts.
+# $name (String) - File name or address of the translations
+translation-overlay-active = Using { $name }
+translation-overlay-file = Load File
+translation-overlay-url = Address of an .ftl file
+translation-overlay-load = Load
+translation-overlay-remove = Remove
+translation-overlay-error = The translations couldn't be loaded.
+
+preferences = Gameplay
+animation-speed = Animation speed
+animation-speed-slow = Slow
+animation-speed-normal = Normal
+animation-speed-fast = Fast
+reduced-motion = Turn off animations
+auto-sort-hand = Sort my hand automatically
+confirm-before-play = Click a card twice to play it
+playable-card-hints = Highlight the cards I can play
+sound-volume = Sound volume
+
+profile = Profile
+profile-name = Name
+profile-avatar = Avatar
+profile-avatar-more = More
+profile-accent = Colour
+player-default-name = Player
+
+stats = Statistics
+stats-games-played = Games played
+stats-games-won = Games won
+stats-average-position = Average position
+stats-cards-drawn = Cards drawn
+stats-longest-streak = Longest winning streak
+stats-most-played-card = Most played card
+stats-missed-hyo-calls = Missed Hyo calls
+# $value (Number) - Any of the statistics
+stats-value = { NUMBER($value, maximumFractionDigits: 2) }
+stats-value-none = –
+# $card (Card) - The card, formatted with CARD()
+stats-card = { CARD($card) }
+stats-history = Recent Matches
+stats-history-empty = No matches played yet.
+# $position (Number) - Finishing position, starting at 1
+# $players (Number) - Number of players
+stats-match-position = { $position } of { $players }
+# $count (Number) - Number of cards drawn
+stats-match-cards-drawn = { $count ->
+        [one] one card drawn
+       *[other] { NUMBER($count) } cards drawn
+    }
+stats-export = Export
+stats-import = Import
+stats-import-error = The file doesn't contain any statistics.
+
+call-hyo = Hyo!
+
+# $name (String) - Name of the achievement
+achievement-unlocked = Achievement unlocked: { $name }
+achievements = Achievements
+achievement-first-win = First Win
+achievement-first-win-description = Win a game.
+achievement-clean-hands = Clean Hands
+achievement-clean-hands-description = Win a game without drawing a single card.
+achievement-comeback = Comeback
+achievement-comeback-description = Win a game after holding 20 cards.
+achievement-lucky-sevens = Lucky Sevens
+achievement-lucky-sevens-description = Play four sevens in one game.
+achievement-loud-and-clear = Loud and Clear
+achievement-loud-and-clear-description = Win a game after calling Hyo without ever forgetting to.
+
+## Game events
+
+# $name (String) - Name of the player
+# $count (Number) - Number of cards drawn
+cards-drawn = { $name } drew { $count ->
+        [one] a card
+       *[other] { NUMBER($count) } cards
+    }
+# $name (String) - Name of the player
+# $card (Card) - The card, formatted with CARD()
+card-played = { $name } played { CARD($card) }
+# $players (List) - Names of the players, formatted with LIST()
+# $count (Number) - Number of players
+players-ready = { LIST($players) } { $count ->
+        [one] is
+       *[other] are
+    } ready
+
+## Used by the LIST(), NUMBER() and CARD() functions
+
+list-separator = {", "}
+list-and = {" and "}
+number-decimal-separator = .
+number-group-separator = ,
+card-color-red = red
+card-color-blue = blue
+card-color-yellow = yellow
+card-color-green = green
@@ -1,19 +1,39 @@
-use crate::game::Game;
+use crate::route::{Route, RouteListener};
 use crate::views::LoadingView;
 use yew::prelude::*;
 
-pub struct App;
+pub struct App {
+    route: Route,
+    _route_listener: RouteListener,
+}
+
+pub enum AppMsg {
+    RouteChanged(Route),
+}
 
 impl Component for App {
-    type Message = ();
+    type Message = AppMsg;
     type Properties = ();
 
-    fn create(_props: Self::Properties, _link: ComponentLink<Self>) -> Self {
-        Self {}
+    fn create(_props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let route_listener = RouteListener::new(link.callback(AppMsg::RouteChanged));
+        Self {
+            route: Route::current(),
+            _route_listener: route_listener,
+        }
     }
 
-    fn update(&mut self, _msg: Self::Message) -> ShouldRender {
-        false
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            AppMsg::RouteChanged(route) => {
+                if self.route != route {
+                    self.route = route;
+                    true
+                } else {
+                    false
+                }
+            }
+        }
     }
 
     fn change(&mut self, _props: Self::Properties) -> bool {
@@ -22,7 +42,7 @@ impl Component for App {
 
     fn view(&self) -> Html {
         html! {
-            <LoadingView/>
+            <LoadingView route=self.route.clone()/>
         }
     }
 }
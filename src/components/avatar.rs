@@ -0,0 +1,45 @@
+use crate::profile::{avatar, Avatar};
+use yew::prelude::*;
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct AvatarImageProps {
+    pub seed: u32,
+}
+
+pub struct AvatarImage {
+    props: AvatarImageProps,
+}
+
+impl Component for AvatarImage {
+    type Message = ();
+    type Properties = AvatarImageProps;
+
+    fn create(props: Self::Properties, _link: ComponentLink<Self>) -> Self {
+        Self { props }
+    }
+
+    fn update(&mut self, _msg: Self::Message) -> ShouldRender {
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> bool {
+        if self.props != props {
+            self.props = props;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn view(&self) -> Html {
+        let avatar = Avatar::new(self.props.seed);
+        // leave half a cell of space around the pattern
+        let view_box = format!("-0.5 -0.5 {0} {0}", avatar::SIZE + 1);
+
+        html! {
+            <svg class="avatar" viewBox=view_box shape-rendering="crispEdges">
+                <path class="avatar__cells" d=avatar.path() fill=avatar.color()/>
+            </svg>
+        }
+    }
+}
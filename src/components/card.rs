@@ -1,11 +1,19 @@
+use serde::{Deserialize, Serialize};
 use yew::prelude::*;
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct CardInfo {
     pub color: String,
     pub number: u8,
 }
 
+impl CardInfo {
+    /// Cards can be played on top of cards with the same color or number.
+    pub fn can_play_on(&self, top: &Self) -> bool {
+        self.color == top.color || self.number == top.number
+    }
+}
+
 #[derive(Clone, PartialEq, Properties)]
 pub struct CardFrontProps {
     pub color: String,
@@ -76,53 +84,3 @@ impl Component for CardBack {
         }
     }
 }
-
-#[derive(Clone, PartialEq, Properties)]
-pub struct CardProps {
-    pub color: String,
-    pub number: u8,
-
-    #[prop_or(0.0)]
-    pub angle: f32,
-
-    #[prop_or_else(Callback::noop)]
-    pub onclick: Callback<MouseEvent>,
-}
-
-pub struct Card {
-    props: CardProps,
-}
-
-impl Component for Card {
-    type Message = ();
-    type Properties = CardProps;
-
-    fn create(props: Self::Properties, _link: ComponentLink<Self>) -> Self {
-        Self { props }
-    }
-
-    fn update(&mut self, _msg: Self::Message) -> ShouldRender {
-        false
-    }
-
-    fn change(&mut self, props: Self::Properties) -> bool {
-        if self.props != props {
-            self.props = props;
-            true
-        } else {
-            false
-        }
-    }
-
-    fn view(&self) -> Html {
-        let props = &self.props;
-        html! {
-            <div class="card-rotate__container" onclick=props.onclick.clone()>
-                <div class="card-rotate" style=format!("transform: rotateY({}rad)", props.angle)>
-                    <CardFront color=props.color.clone() number=props.number/>
-                    <CardBack/>
-                </div>
-            </div>
-        }
-    }
-}
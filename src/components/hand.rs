@@ -2,12 +2,20 @@ use super::{
     card::{CardFront, CardInfo},
     layout::circle::Circle,
 };
+use crate::settings::Settings;
 use yew::prelude::*;
 
 #[derive(Clone, PartialEq, Properties)]
 pub struct HandProps {
     pub cards: Vec<CardInfo>,
 
+    /// Card on top of the play pile, used for hints.
+    #[prop_or_default]
+    pub top_card: Option<CardInfo>,
+    #[prop_or_default]
+    pub settings: Settings,
+
+    /// Emitted with the index of the card in `cards`.
     #[prop_or_else(Callback::noop)]
     pub onclick_card: Callback<usize>,
 }
@@ -15,31 +23,67 @@ pub struct HandProps {
 pub struct Hand {
     props: HandProps,
     link: ComponentLink<Self>,
+    /// Card waiting for a second click to be played.
+    selected: Option<usize>,
 }
 
 pub enum HandMsg {
     CardClick(usize),
 }
 
+impl Hand {
+    /// Indices of the cards in the order they're shown.
+    fn order(&self) -> Vec<usize> {
+        let cards = &self.props.cards;
+        let mut order: Vec<_> = (0..cards.len()).collect();
+        if self.props.settings.auto_sort_hand {
+            order.sort_by(|&a, &b| {
+                (&cards[a].color, cards[a].number).cmp(&(&cards[b].color, cards[b].number))
+            });
+        }
+        order
+    }
+
+    fn is_playable(&self, card: &CardInfo) -> bool {
+        self.props
+            .top_card
+            .as_ref()
+            .is_none_or(|top| card.can_play_on(top))
+    }
+}
+
 impl Component for Hand {
     type Message = HandMsg;
     type Properties = HandProps;
 
     fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
-        Self { props, link }
+        Self {
+            props,
+            link,
+            selected: None,
+        }
     }
 
     fn update(&mut self, msg: Self::Message) -> ShouldRender {
         let props = &self.props;
 
         let HandMsg::CardClick(i) = msg;
-        props.onclick_card.emit(i);
+        if props.settings.confirm_before_play && self.selected != Some(i) {
+            self.selected = Some(i);
+            return true;
+        }
 
-        false
+        self.selected = None;
+        props.onclick_card.emit(i);
+        true
     }
 
     fn change(&mut self, props: Self::Properties) -> bool {
         if self.props != props {
+            // the indices changed or the selection isn't wanted anymore
+            if self.props.cards != props.cards || !props.settings.confirm_before_play {
+                self.selected = None;
+            }
             self.props = props;
             true
         } else {
@@ -48,17 +92,27 @@ impl Component for Hand {
     }
 
     fn view(&self) -> Html {
+        let settings = &self.props.settings;
+
         let cards: Vec<_> = self
-            .props
-            .cards
-            .iter()
-            .cloned()
-            .enumerate()
-            .map(|(i, info)| {
+            .order()
+            .into_iter()
+            .map(|i| {
+                let info = self.props.cards[i].clone();
                 let callback = self.link.callback(move |_| HandMsg::CardClick(i));
 
+                let mut class = String::from("hand__card");
+                if settings.playable_card_hints && self.is_playable(&info) {
+                    class.push_str(" hand__card--playable");
+                }
+                if self.selected == Some(i) {
+                    class.push_str(" hand__card--selected");
+                }
+
                 html! {
-                    <CardFront color=info.color number=info.number onclick=callback/>
+                    <div class=class>
+                        <CardFront color=info.color number=info.number onclick=callback/>
+                    </div>
                 }
             })
             .collect();
@@ -31,9 +31,10 @@ impl Component for CircleItem {
         let props = &self.props;
 
         let (y_dir, x_dir) = (props.angle - 90.0).to_radians().sin_cos();
+        // mirrored for right-to-left languages through `--direction`
         let style = format!(
-            "--base-transform:translateX(-50%) rotate({}deg);\
-            left:calc(var(--circle-radius) * {});\
+            "--base-transform:translateX(-50%) rotate(calc(var(--direction) * {}deg));\
+            left:calc(var(--circle-radius) * var(--direction) * {});\
             bottom:calc(var(--circle-radius) * {});",
             props.angle, x_dir, -y_dir,
         );
@@ -1,5 +1,7 @@
+pub mod avatar;
 pub mod card;
 pub mod hand;
 pub mod icon;
 pub mod layout;
 pub mod pile;
+pub mod qr_code;
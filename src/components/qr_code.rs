@@ -0,0 +1,84 @@
+use crate::qr::{ErrorCorrection, QrCode as QrMatrix};
+use yew::prelude::*;
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct QrCodeProps {
+    pub data: String,
+
+    /// Lowest error correction level to use.
+    #[prop_or_default]
+    pub error_correction: ErrorCorrection,
+    /// Use a higher error correction level if the code doesn't get bigger.
+    #[prop_or(true)]
+    pub boost_error_correction: bool,
+    /// Width of the quiet zone around the code in modules.
+    #[prop_or(4)]
+    pub border: usize,
+}
+
+pub struct QrCode {
+    props: QrCodeProps,
+    matrix: Option<QrMatrix>,
+}
+
+impl QrCode {
+    fn encode(props: &QrCodeProps) -> Option<QrMatrix> {
+        match QrMatrix::encode_text(
+            &props.data,
+            props.error_correction,
+            props.boost_error_correction,
+        ) {
+            Ok(matrix) => Some(matrix),
+            Err(e) => {
+                log::error!("failed to encode QR code for `{}`: {}", props.data, e);
+                None
+            }
+        }
+    }
+}
+
+impl Component for QrCode {
+    type Message = ();
+    type Properties = QrCodeProps;
+
+    fn create(props: Self::Properties, _link: ComponentLink<Self>) -> Self {
+        let matrix = Self::encode(&props);
+        Self { props, matrix }
+    }
+
+    fn update(&mut self, _msg: Self::Message) -> ShouldRender {
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> bool {
+        if self.props != props {
+            self.matrix = Self::encode(&props);
+            self.props = props;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn view(&self) -> Html {
+        let matrix = if let Some(matrix) = &self.matrix {
+            matrix
+        } else {
+            return html! {};
+        };
+
+        let border = self.props.border;
+        let view_size = matrix.size() + 2 * border;
+        let path: String = matrix
+            .dark_modules()
+            .map(|(x, y)| format!("M{},{}h1v1h-1z", x + border, y + border))
+            .collect();
+
+        html! {
+            <svg class="qr-code" viewBox=format!("0 0 {0} {0}", view_size) shape-rendering="crispEdges">
+                <rect class="qr-code__background" width="100%" height="100%"/>
+                <path class="qr-code__modules" d=path/>
+            </svg>
+        }
+    }
+}
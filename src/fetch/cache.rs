@@ -0,0 +1,491 @@
+//! Caching of responses to `GET` requests, meant for static assets like translations.
+//!
+//! A cached response is fresh for the `max-age` its `Cache-Control` header asks for, or the
+//! cache's default max age. After that it's revalidated using its `ETag` or `Last-Modified`
+//! header so unchanged responses aren't downloaded again.
+//!
+//! In stale-while-revalidate mode stale responses are returned right away and revalidated
+//! later by [`Client::revalidate`](super::Client::revalidate), so the app can start with
+//! whatever it has. Requests with a placeholder get it in place of a response that isn't
+//! cached yet. Cached responses remember which placeholder they replaced, so once a new
+//! build ships a different placeholder the old response is no longer used.
+
+use super::{FetchError, HttpRequest, HttpResponse, RequestBuilder, Transport, NOT_MODIFIED};
+use crate::storage::{Key, Storage};
+use serde::{Deserialize, Serialize};
+use std::{
+    cell::RefCell,
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    rc::Rc,
+    time::Duration,
+};
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct CachedResponse {
+    pub body: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    /// When the response was stored or last revalidated, in milliseconds since the Unix epoch.
+    pub stored_at: u64,
+    /// Seconds the server allows the response to be used without revalidating it.
+    pub max_age: Option<u64>,
+    /// Hash of the placeholder of the request that fetched the response.
+    #[serde(default)]
+    pub placeholder: Option<u64>,
+}
+
+impl CachedResponse {
+    /// Stale response with the placeholder body.
+    /// It has no validators, so revalidating it fetches the real response.
+    fn placeholder(body: &str) -> Self {
+        Self {
+            body: body.to_owned(),
+            etag: None,
+            last_modified: None,
+            stored_at: 0,
+            max_age: Some(0),
+            placeholder: Some(hash(body)),
+        }
+    }
+
+    fn to_response(&self) -> HttpResponse {
+        let headers = [("ETag", &self.etag), ("Last-Modified", &self.last_modified)]
+            .iter()
+            .filter_map(|(name, value)| Some(((*name).to_owned(), value.as_ref()?.clone())))
+            .collect();
+        HttpResponse {
+            status: 200,
+            status_text: String::from("OK"),
+            headers,
+            body: self.body.clone(),
+        }
+    }
+}
+
+/// Where cached responses are kept.
+pub trait CacheStore {
+    fn get(&self, url: &str) -> Option<CachedResponse>;
+    fn put(&self, url: &str, response: &CachedResponse);
+}
+
+/// Keeps responses in the app's storage.
+/// Local storage is small, which is fine for a handful of translation files.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StorageStore;
+
+const CACHE_KEY: Key<CachedResponse> = Key::new("fetch-cache");
+
+impl CacheStore for StorageStore {
+    fn get(&self, url: &str) -> Option<CachedResponse> {
+        Storage::local().load(&CACHE_KEY.with_suffix(url))
+    }
+
+    fn put(&self, url: &str, response: &CachedResponse) {
+        if let Err(e) = Storage::local().set(&CACHE_KEY.with_suffix(url), response) {
+            // most likely the quota, the response just won't be cached
+            log::warn!("failed to cache {}: {}", url, e);
+        }
+    }
+}
+
+/// The parts of a `Cache-Control` header the cache cares about.
+#[derive(Debug, Default, PartialEq)]
+struct CacheControl {
+    no_store: bool,
+    max_age: Option<u64>,
+}
+
+impl CacheControl {
+    fn parse(value: Option<&str>) -> Self {
+        let mut directives = Self::default();
+        for directive in value.unwrap_or_default().split(',') {
+            let directive = directive.trim();
+            let (name, arg) = directive.split_once('=').unwrap_or((directive, ""));
+            match name.to_ascii_lowercase().as_str() {
+                "no-store" => directives.no_store = true,
+                "no-cache" => directives.max_age = Some(0),
+                "max-age" => directives.max_age = arg.trim_matches('"').parse().ok(),
+                _ => {}
+            }
+        }
+        directives
+    }
+}
+
+fn millis(duration: Duration) -> u64 {
+    duration.as_millis().min(u64::MAX as u128) as u64
+}
+
+fn hash(placeholder: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    placeholder.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// A request answered with a stale response, along with that response.
+struct Stale {
+    request: HttpRequest,
+    cached: CachedResponse,
+}
+
+pub struct ResponseCache {
+    store: Box<dyn CacheStore>,
+    max_age: Duration,
+    stale_while_revalidate: bool,
+    /// Requests answered with a stale response that haven't been revalidated yet.
+    stale: RefCell<Vec<Stale>>,
+}
+
+impl ResponseCache {
+    /// Cache responses in the store.
+    /// `max_age` applies to responses that don't specify their own.
+    pub fn new(store: impl CacheStore + 'static, max_age: Duration) -> Self {
+        Self {
+            store: Box::new(store),
+            max_age,
+            stale_while_revalidate: false,
+            stale: RefCell::default(),
+        }
+    }
+
+    /// Return stale responses without waiting for them to be revalidated.
+    pub fn stale_while_revalidate(mut self) -> Self {
+        self.stale_while_revalidate = true;
+        self
+    }
+
+    fn is_fresh(&self, cached: &CachedResponse, now: Duration) -> bool {
+        let max_age = cached.max_age.map_or(self.max_age, Duration::from_secs);
+        now.saturating_sub(Duration::from_millis(cached.stored_at)) < max_age
+    }
+
+    fn put(&self, url: &str, resp: &HttpResponse, now: Duration, placeholder: Option<u64>) {
+        let cache_control = CacheControl::parse(resp.header("Cache-Control"));
+        if cache_control.no_store {
+            return;
+        }
+
+        self.store.put(
+            url,
+            &CachedResponse {
+                body: resp.body.clone(),
+                etag: resp.header("ETag").map(ToOwned::to_owned),
+                last_modified: resp.header("Last-Modified").map(ToOwned::to_owned),
+                stored_at: millis(now),
+                max_age: cache_control.max_age,
+                placeholder,
+            },
+        );
+    }
+
+    pub(super) async fn send(&self, builder: &RequestBuilder) -> Result<HttpResponse, FetchError> {
+        let request = &builder.request;
+        let placeholder = builder.placeholder.as_deref().map(hash);
+        // a response cached for another placeholder predates the current build
+        let stored = self
+            .store
+            .get(&request.url)
+            .filter(|cached| cached.placeholder == placeholder);
+        let cached = match (stored, &builder.placeholder) {
+            (Some(cached), _) => cached,
+            (None, Some(placeholder)) if self.stale_while_revalidate => {
+                CachedResponse::placeholder(placeholder)
+            }
+            (None, _) => {
+                let resp = builder.send_with_retries(request).await?;
+                self.put(&request.url, &resp, builder.transport.now(), placeholder);
+                return Ok(resp);
+            }
+        };
+
+        if self.is_fresh(&cached, builder.transport.now()) {
+            return Ok(cached.to_response());
+        }
+
+        if self.stale_while_revalidate {
+            let resp = cached.to_response();
+            let mut stale = self.stale.borrow_mut();
+            if !stale.iter().any(|stale| stale.request.url == request.url) {
+                stale.push(Stale {
+                    request: request.clone(),
+                    cached,
+                });
+            }
+            return Ok(resp);
+        }
+
+        let (resp, _) = self.revalidate_entry(builder, cached).await?;
+        Ok(resp)
+    }
+
+    /// Ask the server whether the cached response is still current.
+    /// Returns the current response and whether it differs from the cached one.
+    /// The cached response is used if the server can't be reached.
+    async fn revalidate_entry(
+        &self,
+        builder: &RequestBuilder,
+        mut cached: CachedResponse,
+    ) -> Result<(HttpResponse, bool), FetchError> {
+        let mut request = builder.request.clone();
+        if let Some(etag) = &cached.etag {
+            request.headers.push(("If-None-Match", etag.clone()));
+        }
+        if let Some(last_modified) = &cached.last_modified {
+            request
+                .headers
+                .push(("If-Modified-Since", last_modified.clone()));
+        }
+
+        match builder.send_with_retries(&request).await {
+            Ok(resp) if resp.status == NOT_MODIFIED => {
+                cached.stored_at = millis(builder.transport.now());
+                if let Some(max_age) = CacheControl::parse(resp.header("Cache-Control")).max_age {
+                    cached.max_age = Some(max_age);
+                }
+                self.store.put(&request.url, &cached);
+                Ok((cached.to_response(), false))
+            }
+            Ok(resp) => {
+                let changed = resp.body != cached.body;
+                let now = builder.transport.now();
+                self.put(&request.url, &resp, now, cached.placeholder);
+                Ok((resp, changed))
+            }
+            Err(FetchError::Aborted) => Err(FetchError::Aborted),
+            Err(err) => {
+                log::warn!(
+                    "failed to revalidate {}, using cached response: {}",
+                    request.url,
+                    err
+                );
+                Ok((cached.to_response(), false))
+            }
+        }
+    }
+
+    pub(super) async fn revalidate(&self, transport: &Rc<dyn Transport>) -> bool {
+        let stale = self.stale.replace(Vec::new());
+        let mut changed = false;
+        for Stale { request, cached } in stale {
+            let mut builder = RequestBuilder::new(Rc::clone(transport), request.method, "");
+            builder.request = request;
+            if let Ok((_, entry_changed)) = self.revalidate_entry(&builder, cached).await {
+                changed |= entry_changed;
+            }
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fetch::{
+        mock::{MemoryStore, MockReply, MockTransport},
+        Client,
+    };
+    use futures::executor::block_on;
+
+    const URL: &str = "locale/fr-FR.ftl";
+    const MAX_AGE: Duration = Duration::from_secs(60);
+
+    fn client(transport: &Rc<MockTransport>, cache: ResponseCache) -> Client {
+        Client::new(Rc::clone(transport) as Rc<dyn Transport>).with_cache(cache)
+    }
+
+    fn get(client: &Client) -> String {
+        block_on(client.get(URL).text()).unwrap()
+    }
+
+    fn if_none_match(transport: &MockTransport) -> Vec<Option<String>> {
+        transport
+            .requests()
+            .iter()
+            .map(|req| {
+                req.headers
+                    .iter()
+                    .find(|(name, _)| *name == "If-None-Match")
+                    .map(|(_, value)| value.clone())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parses_cache_control() {
+        assert_eq!(CacheControl::parse(None), CacheControl::default());
+        assert_eq!(
+            CacheControl::parse(Some("public, max-age=3600")).max_age,
+            Some(3600)
+        );
+        assert_eq!(CacheControl::parse(Some("no-cache")).max_age, Some(0));
+        assert!(CacheControl::parse(Some("No-Store")).no_store);
+    }
+
+    #[test]
+    fn serves_fresh_responses_from_cache() {
+        let transport = Rc::new(MockTransport::new().get(URL, "a"));
+        let client = client(
+            &transport,
+            ResponseCache::new(MemoryStore::default(), MAX_AGE),
+        );
+
+        assert_eq!(get(&client), "a");
+        transport.advance(MAX_AGE / 2);
+        assert_eq!(get(&client), "a");
+        assert_eq!(transport.count(URL), 1);
+    }
+
+    #[test]
+    fn revalidates_stale_responses() {
+        let transport = Rc::new(MockTransport::new().route(
+            "GET",
+            URL,
+            vec![
+                MockReply::ok("a").with_header("ETag", "\"1\""),
+                MockReply::status(NOT_MODIFIED, ""),
+                MockReply::ok("b").with_header("ETag", "\"2\""),
+            ],
+        ));
+        let client = client(
+            &transport,
+            ResponseCache::new(MemoryStore::default(), MAX_AGE),
+        );
+
+        assert_eq!(get(&client), "a");
+        transport.advance(MAX_AGE);
+        assert_eq!(get(&client), "a");
+        transport.advance(MAX_AGE);
+        assert_eq!(get(&client), "b");
+        assert_eq!(
+            if_none_match(&transport),
+            vec![
+                None,
+                Some(String::from("\"1\"")),
+                Some(String::from("\"1\""))
+            ]
+        );
+    }
+
+    #[test]
+    fn honors_cache_control() {
+        let transport = Rc::new(MockTransport::new().route(
+            "GET",
+            URL,
+            vec![
+                MockReply::ok("a").with_header("Cache-Control", "max-age=600"),
+                MockReply::ok("b").with_header("Cache-Control", "no-store"),
+            ],
+        ));
+        let client = client(
+            &transport,
+            ResponseCache::new(MemoryStore::default(), MAX_AGE),
+        );
+
+        assert_eq!(get(&client), "a");
+        transport.advance(Duration::from_secs(300));
+        assert_eq!(get(&client), "a");
+        transport.advance(Duration::from_secs(300));
+        assert_eq!(get(&client), "b");
+        // the no-store response replaced nothing, so the stale one is revalidated again
+        assert_eq!(get(&client), "b");
+        assert_eq!(transport.count(URL), 3);
+    }
+
+    #[test]
+    fn falls_back_to_stale_responses() {
+        let transport = Rc::new(MockTransport::new().route(
+            "GET",
+            URL,
+            vec![MockReply::ok("a"), MockReply::network_error("offline")],
+        ));
+        let client = client(
+            &transport,
+            ResponseCache::new(MemoryStore::default(), MAX_AGE),
+        );
+
+        assert_eq!(get(&client), "a");
+        transport.advance(MAX_AGE);
+        assert_eq!(get(&client), "a");
+    }
+
+    #[test]
+    fn serves_stale_while_revalidating() {
+        let transport = Rc::new(MockTransport::new().route(
+            "GET",
+            URL,
+            vec![MockReply::ok("a"), MockReply::ok("b")],
+        ));
+        let cache = ResponseCache::new(MemoryStore::default(), MAX_AGE).stale_while_revalidate();
+        let client = client(&transport, cache);
+
+        assert_eq!(get(&client), "a");
+        transport.advance(MAX_AGE);
+        assert_eq!(get(&client), "a");
+        assert_eq!(transport.count(URL), 1);
+
+        assert!(block_on(client.revalidate()));
+        assert_eq!(get(&client), "b");
+        assert!(!block_on(client.revalidate()));
+        assert_eq!(transport.count(URL), 2);
+    }
+
+    #[test]
+    fn serves_placeholder_until_revalidated() {
+        let transport = Rc::new(MockTransport::new().get(URL, "b"));
+        let cache = ResponseCache::new(MemoryStore::default(), MAX_AGE).stale_while_revalidate();
+        let client = client(&transport, cache);
+        let get = || block_on(client.get(URL).placeholder("a").text()).unwrap();
+
+        assert_eq!(get(), "a");
+        assert_eq!(transport.count(URL), 0);
+
+        assert!(block_on(client.revalidate()));
+        assert_eq!(get(), "b");
+        assert_eq!(transport.count(URL), 1);
+    }
+
+    #[test]
+    fn keeps_placeholders_out_of_the_store() {
+        let transport = Rc::new(MockTransport::new().get(URL, "b"));
+        let store = MemoryStore::default();
+        let cache = ResponseCache::new(store.clone(), MAX_AGE).stale_while_revalidate();
+        let client = client(&transport, cache);
+
+        assert_eq!(
+            block_on(client.get(URL).placeholder("a").text()).unwrap(),
+            "a"
+        );
+        assert_eq!(store.get(URL), None);
+    }
+
+    #[test]
+    fn ignores_responses_cached_for_another_placeholder() {
+        let transport = Rc::new(MockTransport::new().route(
+            "GET",
+            URL,
+            vec![MockReply::ok("b"), MockReply::network_error("offline")],
+        ));
+        let store = MemoryStore::default();
+        let cache = || ResponseCache::new(store.clone(), MAX_AGE).stale_while_revalidate();
+        let old = client(&transport, cache());
+        assert_eq!(block_on(old.get(URL).placeholder("a").text()).unwrap(), "a");
+        assert!(block_on(old.revalidate()));
+        assert_eq!(block_on(old.get(URL).placeholder("a").text()).unwrap(), "b");
+
+        // a new build with another placeholder, while the old response is still fresh
+        let new = client(&transport, cache());
+        assert_eq!(block_on(new.get(URL).placeholder("c").text()).unwrap(), "c");
+        assert!(!block_on(new.revalidate()));
+        assert_eq!(block_on(new.get(URL).placeholder("c").text()).unwrap(), "c");
+    }
+
+    #[test]
+    fn falls_back_to_placeholder() {
+        let transport = Rc::new(MockTransport::new());
+        let client = Client::new(Rc::clone(&transport) as Rc<dyn Transport>);
+        let res = block_on(client.get(URL).placeholder("a").text());
+        assert_eq!(res.unwrap(), "a");
+        assert_eq!(transport.count(URL), 1);
+    }
+}
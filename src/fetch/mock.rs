@@ -0,0 +1,269 @@
+//! In-memory transport for testing code that makes requests without a browser.
+//!
+//! Responses are looked up by method and URL. Each route replies with its queued responses
+//! in order and keeps repeating the last one, unknown routes get a 404.
+//! Time is simulated: latency and retry delays advance a virtual clock instead of waiting.
+
+use super::{
+    cache::CachedResponse, AbortHandle, CacheStore, FetchError, HttpRequest, HttpResponse,
+    Transport,
+};
+use futures::future::{self, FutureExt, LocalBoxFuture};
+use std::{
+    cell::{Cell, RefCell},
+    collections::{HashMap, VecDeque},
+    rc::Rc,
+    time::Duration,
+};
+
+/// Cache store that keeps its responses in memory.
+#[derive(Clone, Default)]
+pub struct MemoryStore(Rc<RefCell<HashMap<String, CachedResponse>>>);
+
+impl CacheStore for MemoryStore {
+    fn get(&self, url: &str) -> Option<CachedResponse> {
+        self.0.borrow().get(url).cloned()
+    }
+
+    fn put(&self, url: &str, response: &CachedResponse) {
+        self.0.borrow_mut().insert(url.to_owned(), response.clone());
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Outcome {
+    Response(HttpResponse),
+    NetworkError(String),
+}
+
+/// A canned reply to a request.
+#[derive(Clone, Debug)]
+pub struct MockReply {
+    outcome: Outcome,
+    latency: Duration,
+}
+
+impl MockReply {
+    pub fn status(status: u16, body: impl Into<String>) -> Self {
+        Self {
+            outcome: Outcome::Response(HttpResponse {
+                status,
+                status_text: String::new(),
+                headers: Vec::new(),
+                body: body.into(),
+            }),
+            latency: Duration::default(),
+        }
+    }
+
+    pub fn ok(body: impl Into<String>) -> Self {
+        Self::status(200, body)
+    }
+
+    pub fn network_error(message: impl Into<String>) -> Self {
+        Self {
+            outcome: Outcome::NetworkError(message.into()),
+            latency: Duration::default(),
+        }
+    }
+
+    /// Time the reply takes to arrive, it times out if this exceeds the request's timeout.
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        if let Outcome::Response(resp) = &mut self.outcome {
+            resp.headers.push((name.into(), value.into()));
+        }
+        self
+    }
+}
+
+#[derive(Default)]
+pub struct MockTransport {
+    routes: RefCell<HashMap<(String, String), VecDeque<MockReply>>>,
+    requests: RefCell<Vec<HttpRequest>>,
+    clock: Cell<Duration>,
+}
+
+impl MockTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue replies for the route.
+    pub fn route(
+        self,
+        method: &str,
+        url: &str,
+        replies: impl IntoIterator<Item = MockReply>,
+    ) -> Self {
+        self.routes
+            .borrow_mut()
+            .entry((method.to_owned(), url.to_owned()))
+            .or_default()
+            .extend(replies);
+        self
+    }
+
+    /// Reply to `GET` requests for the URL with the body.
+    pub fn get(self, url: &str, body: impl Into<String>) -> Self {
+        self.route("GET", url, vec![MockReply::ok(body)])
+    }
+
+    /// Requests received so far, including failed attempts.
+    pub fn requests(&self) -> Vec<HttpRequest> {
+        self.requests.borrow().clone()
+    }
+
+    /// Number of requests received for the URL.
+    pub fn count(&self, url: &str) -> usize {
+        self.requests
+            .borrow()
+            .iter()
+            .filter(|req| req.url == url)
+            .count()
+    }
+
+    /// Simulated time that has passed.
+    pub fn elapsed(&self) -> Duration {
+        self.clock.get()
+    }
+
+    fn next_reply(&self, request: &HttpRequest) -> MockReply {
+        let mut routes = self.routes.borrow_mut();
+        let key = (request.method.to_owned(), request.url.clone());
+        match routes.get_mut(&key) {
+            Some(replies) if replies.len() > 1 => replies.pop_front().unwrap(),
+            Some(replies) if !replies.is_empty() => replies[0].clone(),
+            _ => MockReply::status(404, "not found"),
+        }
+    }
+
+    /// Let simulated time pass.
+    pub fn advance(&self, duration: Duration) {
+        self.clock.set(self.clock.get() + duration);
+    }
+
+    fn reply(
+        &self,
+        request: &HttpRequest,
+        timeout: Option<Duration>,
+        abort: &AbortHandle,
+    ) -> Result<HttpResponse, FetchError> {
+        if abort.is_aborted() {
+            return Err(FetchError::Aborted);
+        }
+        self.requests.borrow_mut().push(request.clone());
+
+        let reply = self.next_reply(request);
+        if let Some(timeout) = timeout.filter(|&timeout| reply.latency > timeout) {
+            self.advance(timeout);
+            return Err(FetchError::Timeout);
+        }
+        self.advance(reply.latency);
+
+        match reply.outcome {
+            Outcome::Response(resp) => Ok(resp),
+            Outcome::NetworkError(message) => Err(FetchError::Network(message)),
+        }
+    }
+}
+
+impl Transport for MockTransport {
+    fn send<'a>(
+        &'a self,
+        request: &'a HttpRequest,
+        timeout: Option<Duration>,
+        abort: &'a AbortHandle,
+    ) -> LocalBoxFuture<'a, Result<HttpResponse, FetchError>> {
+        future::ready(self.reply(request, timeout, abort)).boxed_local()
+    }
+
+    fn sleep(&self, duration: Duration) -> LocalBoxFuture<'_, Result<(), FetchError>> {
+        self.advance(duration);
+        future::ready(Ok(())).boxed_local()
+    }
+
+    fn now(&self) -> Duration {
+        self.clock.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fetch::Client;
+    use futures::executor::block_on;
+
+    const URL: &str = "data.json";
+
+    fn client(transport: &Rc<MockTransport>) -> Client {
+        Client::new(Rc::clone(transport) as Rc<dyn Transport>)
+    }
+
+    #[test]
+    fn retries_server_errors() {
+        let transport = Rc::new(MockTransport::new().route(
+            "GET",
+            URL,
+            vec![
+                MockReply::status(503, "busy"),
+                MockReply::network_error("offline"),
+                MockReply::ok("[1, 2]"),
+            ],
+        ));
+        let res: Vec<u32> = block_on(client(&transport).get(URL).json()).unwrap();
+        assert_eq!(res, vec![1, 2]);
+        assert_eq!(transport.count(URL), 3);
+        assert_eq!(transport.elapsed(), Duration::from_millis(1500));
+    }
+
+    #[test]
+    fn gives_up_on_client_errors() {
+        let transport = Rc::new(MockTransport::new());
+        let res = block_on(client(&transport).get(URL).text());
+        assert!(matches!(res, Err(FetchError::HttpError { code: 404, .. })));
+        assert_eq!(transport.count(URL), 1);
+    }
+
+    #[test]
+    fn not_modified_needs_a_cache() {
+        let transport =
+            Rc::new(MockTransport::new().route("GET", URL, vec![MockReply::status(304, "")]));
+        let res = block_on(client(&transport).get(URL).text());
+        assert!(matches!(res, Err(FetchError::HttpError { code: 304, .. })));
+        assert_eq!(transport.count(URL), 1);
+    }
+
+    #[test]
+    fn times_out() {
+        let transport = Rc::new(MockTransport::new().route(
+            "GET",
+            URL,
+            vec![MockReply::ok("slow").with_latency(Duration::from_secs(30))],
+        ));
+        let res = block_on(
+            client(&transport)
+                .get(URL)
+                .timeout(Some(Duration::from_secs(1)))
+                .retries(0)
+                .text(),
+        );
+        assert!(matches!(res, Err(FetchError::Timeout)));
+        assert_eq!(transport.elapsed(), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn doesnt_retry_posts() {
+        let transport =
+            Rc::new(MockTransport::new().route("POST", URL, vec![MockReply::status(500, "oops")]));
+        let res = block_on(client(&transport).post(URL).json_body(&[1]).unwrap().text());
+        assert!(matches!(res, Err(FetchError::HttpError { code: 500, .. })));
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].body.as_deref(), Some("[1]"));
+    }
+}
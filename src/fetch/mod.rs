@@ -0,0 +1,398 @@
+mod cache;
+#[cfg(test)]
+pub mod mock;
+mod web;
+
+pub use cache::{CacheStore, ResponseCache, StorageStore};
+use futures::future::LocalBoxFuture;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{rc::Rc, time::Duration};
+use thiserror::Error;
+use wasm_bindgen::{JsCast, JsValue};
+pub use web::{AbortGuard, AbortHandle, WebTransport};
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_RETRIES: u32 = 2;
+const DEFAULT_BACKOFF: Duration = Duration::from_millis(500);
+/// Number of characters of a body included in errors.
+const SNIPPET_LENGTH: usize = 200;
+
+const JSON_CONTENT_TYPE: &str = "application/json";
+const NOT_MODIFIED: u16 = 304;
+
+/// Beginning of the body, for including in error messages.
+fn snippet(body: &str) -> String {
+    match body.char_indices().nth(SNIPPET_LENGTH) {
+        Some((end, _)) => format!("{}…", &body[..end]),
+        None => body.to_owned(),
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum FetchError {
+    #[error("{code}: {text} {}", snippet(.body))]
+    HttpError {
+        code: u16,
+        text: String,
+        /// Body of the response, servers often explain the error there.
+        body: String,
+    },
+
+    #[error("failed to decode response: {source} in `{snippet}`")]
+    Decode {
+        source: serde_json::Error,
+        /// Beginning of the body that couldn't be decoded.
+        snippet: String,
+    },
+
+    // nothing posts yet
+    #[allow(dead_code)]
+    #[error("failed to encode request: {0}")]
+    Encode(serde_json::Error),
+
+    #[error("network error: {0}")]
+    Network(String),
+
+    #[error("request timed out")]
+    Timeout,
+
+    #[error("request aborted")]
+    Aborted,
+
+    #[error("no window available")]
+    NoWindow,
+
+    #[error("error: {0:?}")]
+    Generic(js_sys::Error),
+
+    #[error("unknown error: {0:?}")]
+    Unknown(JsValue),
+}
+
+impl FetchError {
+    /// Whether sending the request again might succeed.
+    /// This is the case for network errors, timeouts and server errors.
+    fn is_retryable(&self) -> bool {
+        match self {
+            Self::HttpError { code, .. } => *code >= 500,
+            Self::Network(_) | Self::Timeout => true,
+            _ => false,
+        }
+    }
+}
+
+impl From<JsValue> for FetchError {
+    fn from(v: JsValue) -> Self {
+        let err_res = v.dyn_into::<js_sys::Error>();
+        match err_res {
+            Ok(err) => Self::Generic(err),
+            Err(err) => Self::Unknown(err),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct HttpRequest {
+    pub method: &'static str,
+    pub url: String,
+    pub headers: Vec<(&'static str, String)>,
+    pub body: Option<String>,
+}
+
+impl HttpRequest {
+    /// Whether the server may answer with 304 Not Modified.
+    /// Only the cache sends these to revalidate its responses.
+    fn is_conditional(&self) -> bool {
+        self.headers
+            .iter()
+            .any(|(name, _)| *name == "If-None-Match" || *name == "If-Modified-Since")
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub status_text: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl HttpResponse {
+    /// Successful response with only a body.
+    fn with_body(body: String) -> Self {
+        Self {
+            status: 200,
+            status_text: String::from("OK"),
+            headers: Vec::new(),
+            body,
+        }
+    }
+
+    pub fn is_ok(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// Value of the header, names are compared case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Sends requests over the network, or pretends to.
+pub trait Transport {
+    /// Send the request once and read the whole response.
+    /// Gives up with `FetchError::Timeout` once the timeout expires and with
+    /// `FetchError::Aborted` once the handle is aborted.
+    fn send<'a>(
+        &'a self,
+        request: &'a HttpRequest,
+        timeout: Option<Duration>,
+        abort: &'a AbortHandle,
+    ) -> LocalBoxFuture<'a, Result<HttpResponse, FetchError>>;
+
+    /// Wait before retrying a request.
+    fn sleep(&self, duration: Duration) -> LocalBoxFuture<'_, Result<(), FetchError>>;
+
+    /// Current time as the duration since the Unix epoch, used to expire cached responses.
+    fn now(&self) -> Duration;
+}
+
+/// Creates requests sent over a transport, which is the browser's `fetch` by default.
+#[derive(Clone)]
+pub struct Client {
+    transport: Rc<dyn Transport>,
+    cache: Option<Rc<ResponseCache>>,
+}
+
+impl Client {
+    pub fn new(transport: Rc<dyn Transport>) -> Self {
+        Self {
+            transport,
+            cache: None,
+        }
+    }
+
+    /// Cache the responses to `GET` requests made by this client.
+    pub fn with_cache(mut self, cache: ResponseCache) -> Self {
+        self.cache = Some(Rc::new(cache));
+        self
+    }
+
+    pub fn request(&self, method: &'static str, url: impl Into<String>) -> RequestBuilder {
+        let mut builder = RequestBuilder::new(Rc::clone(&self.transport), method, url);
+        if method == "GET" {
+            builder.cache = self.cache.clone();
+        }
+        builder
+    }
+
+    pub fn get(&self, url: impl Into<String>) -> RequestBuilder {
+        self.request("GET", url)
+    }
+
+    #[allow(dead_code)]
+    pub fn post(&self, url: impl Into<String>) -> RequestBuilder {
+        self.request("POST", url)
+    }
+
+    /// Revalidate the stale responses the cache served since the last call.
+    /// Returns whether any of them changed, in which case requesting them again returns
+    /// the new version.
+    pub async fn revalidate(&self) -> bool {
+        match &self.cache {
+            Some(cache) => cache.revalidate(&self.transport).await,
+            None => false,
+        }
+    }
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Self::new(Rc::new(WebTransport))
+    }
+}
+
+/// Builder for requests with a timeout, retries and cancellation.
+///
+/// By default requests time out after 10 seconds and `GET` requests are retried twice,
+/// waiting 500ms before the first retry and twice as long before every following one.
+/// Other requests aren't retried unless asked to since they might not be idempotent.
+pub struct RequestBuilder {
+    transport: Rc<dyn Transport>,
+    request: HttpRequest,
+    timeout: Option<Duration>,
+    retries: u32,
+    backoff: Duration,
+    abort: AbortHandle,
+    cache: Option<Rc<ResponseCache>>,
+    placeholder: Option<String>,
+}
+
+impl RequestBuilder {
+    fn new(transport: Rc<dyn Transport>, method: &'static str, url: impl Into<String>) -> Self {
+        Self {
+            transport,
+            request: HttpRequest {
+                method,
+                url: url.into(),
+                headers: Vec::new(),
+                body: None,
+            },
+            timeout: Some(DEFAULT_TIMEOUT),
+            retries: if method == "GET" { DEFAULT_RETRIES } else { 0 },
+            backoff: DEFAULT_BACKOFF,
+            abort: AbortHandle::default(),
+            cache: None,
+            placeholder: None,
+        }
+    }
+
+    /// Get request sent with the browser's `fetch`.
+    pub fn get(url: impl Into<String>) -> Self {
+        Client::default().get(url)
+    }
+
+    /// Post request sent with the browser's `fetch`.
+    #[allow(dead_code)]
+    pub fn post(url: impl Into<String>) -> Self {
+        Client::default().post(url)
+    }
+
+    pub fn header(mut self, name: &'static str, value: impl Into<String>) -> Self {
+        self.request.headers.push((name, value.into()));
+        self
+    }
+
+    /// Ask the server for a JSON response.
+    pub fn accept_json(self) -> Self {
+        self.header("Accept", JSON_CONTENT_TYPE)
+    }
+
+    /// Send the value as a JSON body.
+    #[allow(dead_code)]
+    pub fn json_body<B: Serialize + ?Sized>(self, body: &B) -> Result<Self, FetchError> {
+        let body = serde_json::to_string(body).map_err(FetchError::Encode)?;
+        let mut builder = self.header("Content-Type", JSON_CONTENT_TYPE);
+        builder.request.body = Some(body);
+        Ok(builder)
+    }
+
+    /// Time each attempt may take before it's aborted, `None` waits forever.
+    pub fn timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Number of times the request is sent again after a network or server error.
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Body to use until the real one is known, like a copy compiled into the app.
+    /// A cache in stale-while-revalidate mode serves it right away if it has nothing cached
+    /// yet and fetches the real response on the next revalidation. Otherwise it's used if
+    /// the request fails.
+    pub fn placeholder(mut self, body: impl Into<String>) -> Self {
+        self.placeholder = Some(body.into());
+        self
+    }
+
+    /// Abort the request once the handle's guard is dropped.
+    pub fn abort_with(mut self, handle: &AbortHandle) -> Self {
+        self.abort = handle.clone();
+        self
+    }
+
+    async fn send_once(&self, request: &HttpRequest) -> Result<HttpResponse, FetchError> {
+        let resp = self
+            .transport
+            .send(request, self.timeout, &self.abort)
+            .await?;
+        if resp.is_ok() || (resp.status == NOT_MODIFIED && request.is_conditional()) {
+            Ok(resp)
+        } else {
+            Err(FetchError::HttpError {
+                code: resp.status,
+                text: resp.status_text,
+                body: resp.body,
+            })
+        }
+    }
+
+    /// Time to wait before retrying after the given attempt failed, if it should be retried.
+    fn retry_delay(&self, attempt: u32, err: &FetchError) -> Option<Duration> {
+        if attempt >= self.retries || !err.is_retryable() {
+            return None;
+        }
+
+        log::warn!(
+            "{} {} failed, retrying ({}/{}): {}",
+            self.request.method,
+            self.request.url,
+            attempt + 1,
+            self.retries,
+            err
+        );
+        Some(self.backoff * 2u32.saturating_pow(attempt))
+    }
+
+    /// Send the request over the network, retrying it if necessary.
+    async fn send_with_retries(&self, request: &HttpRequest) -> Result<HttpResponse, FetchError> {
+        let mut attempt = 0;
+        loop {
+            match self.send_once(request).await {
+                Ok(resp) => return Ok(resp),
+                Err(err) => match self.retry_delay(attempt, &err) {
+                    Some(delay) => {
+                        self.transport.sleep(delay).await?;
+                        if self.abort.is_aborted() {
+                            return Err(FetchError::Aborted);
+                        }
+                    }
+                    None => return Err(err),
+                },
+            }
+            attempt += 1;
+        }
+    }
+
+    /// Send the request, retrying it if necessary.
+    /// Responses with a status other than 2xx are returned as `FetchError::HttpError`.
+    pub async fn send(&self) -> Result<HttpResponse, FetchError> {
+        let res = match &self.cache {
+            Some(cache) => cache.send(self).await,
+            None => self.send_with_retries(&self.request).await,
+        };
+        match (res, &self.placeholder) {
+            (Err(FetchError::Aborted), _) => Err(FetchError::Aborted),
+            (Err(err), Some(placeholder)) => {
+                log::warn!(
+                    "{} {} failed, using placeholder: {}",
+                    self.request.method,
+                    self.request.url,
+                    err
+                );
+                Ok(HttpResponse::with_body(placeholder.clone()))
+            }
+            (res, _) => res,
+        }
+    }
+
+    /// Send the request and return the body.
+    pub async fn text(&self) -> Result<String, FetchError> {
+        Ok(self.send().await?.body)
+    }
+
+    /// Send the request and decode the JSON body.
+    pub async fn json<T: DeserializeOwned>(&self) -> Result<T, FetchError> {
+        let text = self.text().await?;
+        serde_json::from_str(&text).map_err(|source| FetchError::Decode {
+            source,
+            snippet: snippet(&text),
+        })
+    }
+}
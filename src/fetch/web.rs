@@ -0,0 +1,251 @@
+//! Transport using the browser's `fetch`.
+
+use super::{FetchError, HttpRequest, HttpResponse, Transport};
+use futures::future::{FutureExt, LocalBoxFuture};
+use std::{cell::Cell, rc::Rc, time::Duration};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    AbortController, AbortSignal, Headers, Request, RequestInit, RequestMode, Response, Window,
+};
+
+async fn read_text(resp: &Response) -> Result<String, JsValue> {
+    let text_val = JsFuture::from(resp.text()?).await?;
+    Ok(text_val.as_string().unwrap_or_default())
+}
+
+fn read_headers(headers: &Headers) -> Vec<(String, String)> {
+    let entries = match js_sys::try_iter(headers) {
+        Ok(Some(entries)) => entries,
+        _ => return Vec::new(),
+    };
+    entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let entry: js_sys::Array = entry.dyn_into().ok()?;
+            Some((entry.get(0).as_string()?, entry.get(1).as_string()?))
+        })
+        .collect()
+}
+
+fn duration_millis(duration: Duration) -> i32 {
+    duration.as_millis().min(i32::MAX as u128) as i32
+}
+
+async fn sleep(window: &Window, duration: Duration) -> Result<(), FetchError> {
+    let mut res = Ok(0);
+    let promise = js_sys::Promise::new(&mut |resolve, _reject| {
+        res = window.set_timeout_with_callback_and_timeout_and_arguments_0(
+            &resolve,
+            duration_millis(duration),
+        );
+    });
+    res?;
+    JsFuture::from(promise).await?;
+    Ok(())
+}
+
+/// Aborts all requests made with it once it's dropped.
+/// Components keep one around so their requests are cancelled when they're destroyed.
+pub struct AbortGuard {
+    controller: Option<AbortController>,
+}
+
+impl AbortGuard {
+    pub fn new() -> Self {
+        let controller = match AbortController::new() {
+            Ok(controller) => Some(controller),
+            Err(e) => {
+                log::warn!("failed to create abort controller: {:?}", e);
+                None
+            }
+        };
+        Self { controller }
+    }
+
+    /// Handle for linking requests to the guard, even after it has been moved into a future.
+    pub fn handle(&self) -> AbortHandle {
+        AbortHandle {
+            signal: self.controller.as_ref().map(AbortController::signal),
+        }
+    }
+}
+
+impl Default for AbortGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for AbortGuard {
+    fn drop(&mut self) {
+        if let Some(controller) = &self.controller {
+            controller.abort();
+        }
+    }
+}
+
+/// Cancels the requests it's passed to once the guard it came from is dropped.
+/// The default handle never cancels anything.
+#[derive(Clone, Debug, Default)]
+pub struct AbortHandle {
+    signal: Option<AbortSignal>,
+}
+
+impl AbortHandle {
+    pub fn is_aborted(&self) -> bool {
+        self.signal.as_ref().is_some_and(AbortSignal::aborted)
+    }
+}
+
+/// A single attempt at sending a request.
+/// The request is aborted once the timeout expires or the guard's signal is triggered for
+/// as long as the attempt is alive.
+struct Attempt {
+    window: Window,
+    controller: AbortController,
+    timed_out: Rc<Cell<bool>>,
+    timeout: Option<(i32, Closure<dyn FnMut()>)>,
+    guard: Option<(AbortSignal, Closure<dyn FnMut()>)>,
+}
+
+impl Attempt {
+    fn new(
+        window: &Window,
+        timeout: Option<Duration>,
+        guard: Option<&AbortSignal>,
+    ) -> Result<Self, FetchError> {
+        let controller = AbortController::new()?;
+        let mut attempt = Self {
+            window: window.clone(),
+            controller,
+            timed_out: Rc::new(Cell::new(false)),
+            timeout: None,
+            guard: None,
+        };
+
+        if let Some(signal) = guard {
+            if signal.aborted() {
+                return Err(FetchError::Aborted);
+            }
+
+            let controller = attempt.controller.clone();
+            let closure = Closure::wrap(Box::new(move || controller.abort()) as Box<dyn FnMut()>);
+            signal.add_event_listener_with_callback("abort", closure.as_ref().unchecked_ref())?;
+            attempt.guard = Some((signal.clone(), closure));
+        }
+
+        if let Some(timeout) = timeout {
+            let controller = attempt.controller.clone();
+            let timed_out = Rc::clone(&attempt.timed_out);
+            let closure = Closure::wrap(Box::new(move || {
+                timed_out.set(true);
+                controller.abort();
+            }) as Box<dyn FnMut()>);
+            let handle = window.set_timeout_with_callback_and_timeout_and_arguments_0(
+                closure.as_ref().unchecked_ref(),
+                duration_millis(timeout),
+            )?;
+            attempt.timeout = Some((handle, closure));
+        }
+
+        Ok(attempt)
+    }
+
+    fn signal(&self) -> AbortSignal {
+        self.controller.signal()
+    }
+
+    /// Convert an error raised while the attempt was active.
+    /// `fetch` rejects with a `TypeError` when the network fails.
+    fn error(&self, err: JsValue) -> FetchError {
+        if self.timed_out.get() {
+            FetchError::Timeout
+        } else if self.controller.signal().aborted() {
+            FetchError::Aborted
+        } else if let Some(err) = err.dyn_ref::<js_sys::TypeError>() {
+            FetchError::Network(String::from(err.message()))
+        } else {
+            err.into()
+        }
+    }
+}
+
+impl Drop for Attempt {
+    fn drop(&mut self) {
+        if let Some((handle, _)) = &self.timeout {
+            self.window.clear_timeout_with_handle(*handle);
+        }
+        if let Some((signal, closure)) = &self.guard {
+            let _ = signal
+                .remove_event_listener_with_callback("abort", closure.as_ref().unchecked_ref());
+        }
+    }
+}
+
+/// Sends requests with `window.fetch`.
+/// The timeout covers reading the body.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct WebTransport;
+
+impl WebTransport {
+    async fn fetch(
+        request: &HttpRequest,
+        timeout: Option<Duration>,
+        abort: &AbortHandle,
+    ) -> Result<HttpResponse, FetchError> {
+        let window = web_sys::window().ok_or(FetchError::NoWindow)?;
+        let attempt = Attempt::new(&window, timeout, abort.signal.as_ref())?;
+
+        let opts = RequestInit::new();
+        opts.set_method(request.method);
+        opts.set_mode(RequestMode::Cors);
+        opts.set_signal(Some(&attempt.signal()));
+        if !request.headers.is_empty() {
+            let headers = Headers::new()?;
+            for (name, value) in &request.headers {
+                headers.append(name, value)?;
+            }
+            opts.set_headers(&headers);
+        }
+        if let Some(body) = &request.body {
+            opts.set_body(&JsValue::from_str(body));
+        }
+        let js_request = Request::new_with_str_and_init(&request.url, &opts)?;
+
+        let resp_value = JsFuture::from(window.fetch_with_request(&js_request))
+            .await
+            .map_err(|err| attempt.error(err))?;
+        let resp: Response = resp_value.dyn_into().map_err(FetchError::Unknown)?;
+        let body = read_text(&resp).await.map_err(|err| attempt.error(err))?;
+        Ok(HttpResponse {
+            status: resp.status(),
+            status_text: resp.status_text(),
+            headers: read_headers(&resp.headers()),
+            body,
+        })
+    }
+}
+
+impl Transport for WebTransport {
+    fn send<'a>(
+        &'a self,
+        request: &'a HttpRequest,
+        timeout: Option<Duration>,
+        abort: &'a AbortHandle,
+    ) -> LocalBoxFuture<'a, Result<HttpResponse, FetchError>> {
+        Self::fetch(request, timeout, abort).boxed_local()
+    }
+
+    fn sleep(&self, duration: Duration) -> LocalBoxFuture<'_, Result<(), FetchError>> {
+        async move {
+            let window = web_sys::window().ok_or(FetchError::NoWindow)?;
+            sleep(&window, duration).await
+        }
+        .boxed_local()
+    }
+
+    fn now(&self) -> Duration {
+        Duration::from_millis(js_sys::Date::now() as u64)
+    }
+}
@@ -0,0 +1,86 @@
+//! Everything that happens in a game is recorded as a list of events.
+//!
+//! Statistics and achievements are computed from the events once a match is over, so the
+//! list is all that needs to be kept to evaluate a game again later.
+
+use crate::components::card::CardInfo;
+use serde::{Deserialize, Serialize};
+
+/// Players are referenced by their index in the list of players at the table.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum GameEvent {
+    /// The player was dealt their starting hand.
+    Deal {
+        player: usize,
+        count: u32,
+    },
+    Draw {
+        player: usize,
+        count: u32,
+    },
+    Play {
+        player: usize,
+        card: CardInfo,
+    },
+    /// The player called "Hyo" before playing their second to last card.
+    CallHyo {
+        player: usize,
+    },
+    /// The player was left with a single card without calling "Hyo".
+    MissHyo {
+        player: usize,
+    },
+    /// The player got rid of all their cards.
+    Finish {
+        player: usize,
+    },
+}
+
+impl GameEvent {
+    pub fn player(&self) -> usize {
+        match *self {
+            Self::Deal { player, .. }
+            | Self::Draw { player, .. }
+            | Self::Play { player, .. }
+            | Self::CallHyo { player }
+            | Self::MissHyo { player }
+            | Self::Finish { player } => player,
+        }
+    }
+}
+
+/// Whether the player called "Hyo" since they last played a card.
+pub fn has_called_hyo(events: &[GameEvent], player: usize) -> bool {
+    events
+        .iter()
+        .rev()
+        .filter(|event| event.player() == player)
+        .take_while(|event| !matches!(event, GameEvent::Play { .. }))
+        .any(|event| matches!(event, GameEvent::CallHyo { .. }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hyo_call_lasts_until_next_play() {
+        let card = CardInfo {
+            color: String::from("red"),
+            number: 3,
+        };
+        let mut events = vec![GameEvent::CallHyo { player: 0 }];
+        assert!(has_called_hyo(&events, 0));
+        assert!(!has_called_hyo(&events, 1));
+
+        events.push(GameEvent::Draw {
+            player: 1,
+            count: 1,
+        });
+        assert!(has_called_hyo(&events, 0));
+
+        events.push(GameEvent::Play { player: 0, card });
+        assert!(!has_called_hyo(&events, 0));
+    }
+}
@@ -0,0 +1,357 @@
+mod event;
+mod save;
+
+use crate::{
+    components::{
+        avatar::AvatarImage,
+        card::CardInfo,
+        hand::Hand,
+        pile::{HiddenPile, VisiblePile},
+    },
+    locale::{messages, Locale},
+    profile::{
+        achievements::{self, Achievement},
+        stats::Stats,
+        PlayerInfo, Profile,
+    },
+    settings::Settings,
+    storage::Storage,
+};
+pub use event::GameEvent;
+pub use save::{clear_saved_game, has_saved_game};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use yew::{
+    prelude::*,
+    services::{timeout::TimeoutTask, TimeoutService},
+};
+
+type Cards = Vec<CardInfo>;
+
+/// How long an unlocked achievement is announced for.
+const TOAST_DURATION: Duration = Duration::from_secs(5);
+
+/// Index of the local player in the list of players.
+const LOCAL_PLAYER: usize = 0;
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+struct GameState {
+    /// Players at the table in turn order.
+    players: Vec<PlayerInfo>,
+    draw_pile: Cards,
+    play_pile: Cards,
+    holding: Cards,
+    events: Vec<GameEvent>,
+}
+
+impl GameState {
+    /// The match is over once the local player finished.
+    fn is_finished(&self) -> bool {
+        self.events.iter().any(|event| match event {
+            GameEvent::Finish { player } => *player == LOCAL_PLAYER,
+            _ => false,
+        })
+    }
+
+    /// Apply a move of the local player and return whether anything changed.
+    /// Moves are ignored once the match is over.
+    fn apply(&mut self, msg: GameMsg) -> bool {
+        if self.is_finished() {
+            log::debug!("ignoring move after the match is over");
+            return false;
+        }
+
+        match msg {
+            GameMsg::HandCardClicked(i) => {
+                log::debug!("playing card with index {}", i);
+                if i < self.holding.len() {
+                    let called_hyo = event::has_called_hyo(&self.events, LOCAL_PLAYER);
+                    let card = self.holding.remove(i);
+                    self.events.push(GameEvent::Play {
+                        player: LOCAL_PLAYER,
+                        card: card.clone(),
+                    });
+                    self.play_pile.push(card);
+
+                    if self.holding.len() == 1 && !called_hyo {
+                        self.events.push(GameEvent::MissHyo {
+                            player: LOCAL_PLAYER,
+                        });
+                    } else if self.holding.is_empty() {
+                        self.events.push(GameEvent::Finish {
+                            player: LOCAL_PLAYER,
+                        });
+                    }
+                    true
+                } else {
+                    log::warn!("failed to play card with index {}", i);
+                    false
+                }
+            }
+            GameMsg::DrawPileClicked => {
+                if let Some(card) = self.draw_pile.pop() {
+                    log::debug!("drawing card");
+                    self.holding.push(card);
+                    self.events.push(GameEvent::Draw {
+                        player: LOCAL_PLAYER,
+                        count: 1,
+                    });
+                    true
+                } else {
+                    false
+                }
+            }
+            GameMsg::CallHyo => {
+                if event::has_called_hyo(&self.events, LOCAL_PLAYER) {
+                    false
+                } else {
+                    self.events.push(GameEvent::CallHyo {
+                        player: LOCAL_PLAYER,
+                    });
+                    true
+                }
+            }
+            GameMsg::DismissToast => false,
+        }
+    }
+
+    /// Add the finished match to the statistics of the local player.
+    fn record_stats(&self, storage: &Storage) {
+        let mut stats = Stats::load(storage);
+        stats.record(
+            LOCAL_PLAYER,
+            self.players.len(),
+            &self.events,
+            js_sys::Date::now(),
+        );
+        if let Err(err) = stats.store(storage) {
+            log::error!("failed to store stats: {}", err);
+        }
+    }
+
+    /// Unlock the achievements the local player earned in the finished match.
+    /// Returns the ones that are new to the player.
+    fn unlock_achievements(&self, storage: &Storage) -> Vec<&'static Achievement> {
+        let mut profile = Profile::load(storage);
+        let earned = achievements::evaluate(LOCAL_PLAYER, &self.events);
+        let new = profile.unlock(earned, js_sys::Date::now());
+        if !new.is_empty() {
+            if let Err(err) = profile.store(storage) {
+                log::error!("failed to store achievements: {}", err);
+            }
+        }
+        new
+    }
+
+    fn test() -> Self {
+        let cards = vec![
+            CardInfo {
+                color: String::from("red"),
+                number: 8,
+            },
+            CardInfo {
+                color: String::from("red"),
+                number: 5,
+            },
+            CardInfo {
+                color: String::from("red"),
+                number: 7,
+            },
+            CardInfo {
+                color: String::from("blue"),
+                number: 4,
+            },
+            CardInfo {
+                color: String::from("yellow"),
+                number: 1,
+            },
+            CardInfo {
+                color: String::from("green"),
+                number: 0,
+            },
+            CardInfo {
+                color: String::from("green"),
+                number: 2,
+            },
+        ];
+
+        Self {
+            players: Vec::new(),
+            draw_pile: cards.clone(),
+            play_pile: vec![CardInfo {
+                color: String::from("green"),
+                number: 2,
+            }],
+            holding: cards,
+            events: Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct GameProps {
+    pub locale: Locale,
+    pub settings: Settings,
+}
+
+pub struct Game {
+    props: GameProps,
+    link: ComponentLink<Self>,
+    state: GameState,
+    /// Achievements that are being announced.
+    unlocked: Vec<&'static Achievement>,
+    toast_timeout: Option<TimeoutTask>,
+}
+
+pub enum GameMsg {
+    HandCardClicked(usize),
+    DrawPileClicked,
+    CallHyo,
+    DismissToast,
+}
+
+impl Game {
+    fn announce(&mut self, achievements: Vec<&'static Achievement>) {
+        if achievements.is_empty() {
+            return;
+        }
+
+        self.unlocked.extend(achievements);
+        let callback = self.link.callback(|_| GameMsg::DismissToast);
+        self.toast_timeout = Some(TimeoutService::new().spawn(TOAST_DURATION, callback));
+    }
+
+    fn view_toast(&self) -> Html {
+        let locale = &self.props.locale;
+        if self.unlocked.is_empty() {
+            return html! {};
+        }
+
+        let achievements = self.unlocked.iter().map(|achievement| {
+            let name = locale.localize(&achievement.name).into_owned();
+            html! {
+                <li class="toast__item">
+                    <strong>{ locale.localize(&messages::achievement_unlocked(name)) }</strong>
+                    <span>{ locale.localize(&achievement.description) }</span>
+                </li>
+            }
+        });
+        let onclick = self.link.callback(|_| GameMsg::DismissToast);
+
+        html! {
+            <ul class="toast" role="status" onclick=onclick>
+                { for achievements }
+            </ul>
+        }
+    }
+}
+
+impl Component for Game {
+    type Message = GameMsg;
+    type Properties = GameProps;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let storage = Storage::local();
+        let state = save::load(&storage).unwrap_or_else(|| {
+            let mut state = GameState::test();
+            state.players.push(Profile::load(&storage).player_info());
+            state.events.push(GameEvent::Deal {
+                player: LOCAL_PLAYER,
+                count: state.holding.len() as u32,
+            });
+            state
+        });
+        Self {
+            props,
+            link,
+            state,
+            unlocked: Vec::new(),
+            toast_timeout: None,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        if let GameMsg::DismissToast = msg {
+            self.toast_timeout = None;
+            self.unlocked.clear();
+            return true;
+        }
+
+        let changed = self.state.apply(msg);
+        if changed {
+            let storage = Storage::local();
+            if self.state.is_finished() {
+                self.state.record_stats(&storage);
+                self.announce(self.state.unlock_achievements(&storage));
+                save::clear(&storage);
+            } else {
+                save::store(&storage, &self.state);
+            }
+        }
+        changed
+    }
+
+    fn change(&mut self, props: Self::Properties) -> bool {
+        if self.props != props {
+            self.props = props;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn view(&self) -> Html {
+        let state = &self.state;
+
+        let draw_pile_callback = self.link.callback(|_| GameMsg::DrawPileClicked);
+        let hand_callback = self.link.callback(GameMsg::HandCardClicked);
+        let onclick_hyo = self.link.callback(|_| GameMsg::CallHyo);
+
+        let locale = &self.props.locale;
+        let players = state.players.iter().map(|player| {
+            html! {
+                <li class="players__player">
+                    <AvatarImage seed=player.avatar/>
+                    <span>{ player.display_name(locale) }</span>
+                </li>
+            }
+        });
+
+        html! {
+            <div class="table">
+                <ul class="players">
+                    { for players }
+                </ul>
+                <Hand cards=state.holding.clone() top_card=state.play_pile.last().cloned() settings=self.props.settings.clone() onclick_card=hand_callback/>
+                <button class="table__hyo button-row__btn" onclick=onclick_hyo>{ locale.localize(&messages::CALL_HYO) }</button>
+                { self.view_toast() }
+                <div class="piles">
+                    <VisiblePile cards=state.play_pile.clone()/>
+                    <HiddenPile cards=state.draw_pile.len() onclick=draw_pile_callback/>
+                </div>
+            </div>
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ignores_moves_after_the_match() {
+        let mut state = GameState::test();
+        while state.holding.len() > 1 {
+            assert!(state.apply(GameMsg::HandCardClicked(0)));
+        }
+        assert!(!state.is_finished());
+        assert!(state.apply(GameMsg::HandCardClicked(0)));
+        assert!(state.is_finished());
+
+        let finished = state.clone();
+        assert!(!state.apply(GameMsg::CallHyo));
+        assert!(!state.apply(GameMsg::DrawPileClicked));
+        assert!(!state.apply(GameMsg::HandCardClicked(0)));
+        assert_eq!(state, finished);
+    }
+}
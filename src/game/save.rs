@@ -0,0 +1,170 @@
+//! Saving local games so they can be resumed after the tab is closed.
+//!
+//! The game state is saved after every move together with the version of the save format.
+//! Saves written by older versions are upgraded by the migrations in [`MIGRATIONS`], saves
+//! that can't be upgraded or come from a newer version are discarded.
+
+use super::GameState;
+use crate::storage::{Key, Storage};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+const SAVE_KEY: Key<SavedGame> = Key::new("saved-game");
+
+/// Upgrades a saved state to the next version, `None` if it can't be upgraded.
+type Migration = fn(Value) -> Option<Value>;
+
+/// Upgrades for older saves, the first one upgrades version 1 to version 2.
+const MIGRATIONS: &[Migration] = &[];
+
+/// Current version of the save format.
+const VERSION: u32 = MIGRATIONS.len() as u32 + 1;
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct SavedGame {
+    version: u32,
+    /// Kept as raw JSON until it has been upgraded to the current version.
+    state: Value,
+}
+
+/// Upgrade the saved game to the current version of `GameState`.
+fn upgrade(saved: SavedGame, migrations: &[Migration]) -> Option<GameState> {
+    let latest = migrations.len() as u32 + 1;
+    if saved.version == 0 || saved.version > latest {
+        log::warn!(
+            "discarding saved game with unknown version {}",
+            saved.version
+        );
+        return None;
+    }
+
+    let mut state = saved.state;
+    for (i, migration) in migrations
+        .iter()
+        .enumerate()
+        .skip(saved.version as usize - 1)
+    {
+        state = match migration(state) {
+            Some(state) => state,
+            None => {
+                log::warn!(
+                    "discarding saved game that can't be upgraded to version {}",
+                    i + 2
+                );
+                return None;
+            }
+        };
+    }
+
+    match serde_json::from_value(state) {
+        Ok(state) => Some(state),
+        Err(err) => {
+            log::warn!("discarding unreadable saved game: {}", err);
+            None
+        }
+    }
+}
+
+/// Load the saved game, discarding it if it can't be used anymore.
+pub(super) fn load(storage: &Storage) -> Option<GameState> {
+    let saved = storage.load(&SAVE_KEY)?;
+    let state = upgrade(saved, MIGRATIONS);
+    if state.is_none() {
+        clear(storage);
+    }
+    state
+}
+
+pub(super) fn store(storage: &Storage, state: &GameState) {
+    let res = serde_json::to_value(state)
+        .map_err(|err| err.to_string())
+        .and_then(|state| {
+            let saved = SavedGame {
+                version: VERSION,
+                state,
+            };
+            storage
+                .set(&SAVE_KEY, &saved)
+                .map_err(|err| err.to_string())
+        });
+    if let Err(err) = res {
+        log::error!("failed to save game: {}", err);
+    }
+}
+
+pub(super) fn clear(storage: &Storage) {
+    if let Err(err) = storage.remove(&SAVE_KEY) {
+        log::error!("failed to remove saved game: {}", err);
+    }
+}
+
+/// Whether there's a game that can be resumed.
+pub fn has_saved_game() -> bool {
+    load(&Storage::local()).is_some()
+}
+
+/// Throw away the saved game so the next one starts from scratch.
+pub fn clear_saved_game() {
+    clear(&Storage::local());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn saved(version: u32, state: Value) -> SavedGame {
+        SavedGame { version, state }
+    }
+
+    #[test]
+    fn resumes_saved_game() {
+        let storage = Storage::memory();
+        assert_eq!(load(&storage), None);
+
+        let mut state = GameState::test();
+        state.draw_pile.pop();
+        store(&storage, &state);
+        assert_eq!(load(&storage), Some(state));
+
+        clear(&storage);
+        assert_eq!(load(&storage), None);
+    }
+
+    #[test]
+    fn discards_unusable_saves() {
+        let storage = Storage::memory();
+        storage
+            .set(&SAVE_KEY, &saved(VERSION + 1, json!({})))
+            .unwrap();
+        assert_eq!(load(&storage), None);
+        assert_eq!(storage.get(&SAVE_KEY).unwrap(), None);
+
+        storage
+            .set(&SAVE_KEY, &saved(VERSION, json!({ "draw_pile": 3 })))
+            .unwrap();
+        assert_eq!(load(&storage), None);
+        assert_eq!(storage.get(&SAVE_KEY).unwrap(), None);
+    }
+
+    fn rename_hand(mut state: Value) -> Option<Value> {
+        let hand = state.as_object_mut()?.remove("hand")?;
+        state["holding"] = hand;
+        Some(state)
+    }
+
+    #[test]
+    fn upgrades_old_saves() {
+        let expected = GameState::test();
+        let mut old = serde_json::to_value(&expected).unwrap();
+        let holding = old.as_object_mut().unwrap().remove("holding").unwrap();
+        old["hand"] = holding;
+
+        assert_eq!(
+            upgrade(saved(1, old.clone()), &[rename_hand]),
+            Some(expected)
+        );
+        assert_eq!(upgrade(saved(1, json!({})), &[rename_hand]), None);
+        assert_eq!(upgrade(saved(2, old), &[rename_hand]), None);
+    }
+}
@@ -1,12 +1,18 @@
+#![recursion_limit = "256"]
+
 mod app;
 mod components;
 mod fetch;
 mod game;
 mod locale;
+mod profile;
+mod qr;
+mod route;
+mod settings;
+mod storage;
 mod views;
 
 use wasm_bindgen::prelude::*;
-use wasm_logger;
 
 #[wasm_bindgen]
 pub fn run_app() -> Result<(), JsValue> {
@@ -0,0 +1,33 @@
+//! Locales compiled into the binary, used until the server's version is known.
+//! With the `embed-locales` feature disabled there are none and everything is fetched at runtime.
+
+use std::collections::BTreeMap;
+use unic_langid::LanguageIdentifier;
+
+pub struct EmbeddedLocale {
+    pub id: &'static str,
+    /// Name of the language in the language itself.
+    pub name: &'static str,
+    pub source: &'static str,
+}
+
+pub static LOCALES: &[EmbeddedLocale] = include!(concat!(env!("OUT_DIR"), "/embedded_locales.rs"));
+
+pub fn get(langid: &LanguageIdentifier) -> Option<&'static EmbeddedLocale> {
+    let id = langid.to_string();
+    LOCALES.iter().find(|locale| locale.id == id)
+}
+
+/// Manifest of the embedded locales in the format of `locale/index.json`.
+/// `None` if there are no embedded locales.
+pub fn manifest() -> Option<String> {
+    if LOCALES.is_empty() {
+        return None;
+    }
+
+    let manifest: BTreeMap<_, _> = LOCALES
+        .iter()
+        .map(|locale| (locale.id, locale.name))
+        .collect();
+    serde_json::to_string(&manifest).ok()
+}
@@ -0,0 +1,436 @@
+//! Custom functions available to messages.
+//!
+//! - `LIST($items)` joins a [`List`] using the `list-separator` and `list-and` messages.
+//! - `NUMBER($num)` formats a number with the `number-*-separator` messages. It accepts the
+//!   `minimumFractionDigits`, `maximumFractionDigits` and `useGrouping` options.
+//!   The result is a string so selectors should use the number itself.
+//! - `CARD($card)` names a card using the `card-color-*` messages.
+//!
+//! Functions can't reference messages while a message is formatted, so the messages they
+//! need are resolved once when they're added to the bundles.
+//!
+//! Lists and cards are custom Fluent values. Messages that use them without their function
+//! get a plain rendering that isn't localized.
+
+use crate::components::card::CardInfo;
+use fluent::{types::FluentType, FluentArgs, FluentBundle, FluentResource, FluentValue};
+use std::{
+    borrow::{Borrow, Cow},
+    collections::HashMap,
+    iter::FromIterator,
+};
+
+/// Colors that have a `card-color-*` message.
+const CARD_COLORS: [&str; 4] = ["red", "blue", "yellow", "green"];
+
+/// Maximum fraction digits if the number doesn't specify any.
+const DEFAULT_MAX_FRACTION_DIGITS: usize = 3;
+
+/// A list of items formatted with `LIST()`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct List(Vec<String>);
+
+impl<T: Into<String>> FromIterator<T> for List {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self(iter.into_iter().map(Into::into).collect())
+    }
+}
+
+impl FluentType for List {
+    fn duplicate(&self) -> Box<dyn FluentType> {
+        Box::new(self.clone())
+    }
+
+    fn as_string(&self, _intls: &intl_memoizer::IntlLangMemoizer) -> Cow<'static, str> {
+        self.0.join(", ").into()
+    }
+
+    fn as_string_threadsafe(
+        &self,
+        _intls: &intl_memoizer::concurrent::IntlLangMemoizer,
+    ) -> Cow<'static, str> {
+        self.0.join(", ").into()
+    }
+}
+
+impl<'a> From<List> for FluentValue<'a> {
+    fn from(list: List) -> Self {
+        Self::Custom(Box::new(list))
+    }
+}
+
+impl FluentType for CardInfo {
+    fn duplicate(&self) -> Box<dyn FluentType> {
+        Box::new(self.clone())
+    }
+
+    fn as_string(&self, _intls: &intl_memoizer::IntlLangMemoizer) -> Cow<'static, str> {
+        format!("{} {}", self.color, self.number).into()
+    }
+
+    fn as_string_threadsafe(
+        &self,
+        _intls: &intl_memoizer::concurrent::IntlLangMemoizer,
+    ) -> Cow<'static, str> {
+        format!("{} {}", self.color, self.number).into()
+    }
+}
+
+impl<'a> From<&CardInfo> for FluentValue<'a> {
+    fn from(card: &CardInfo) -> Self {
+        Self::Custom(Box::new(card.clone()))
+    }
+}
+
+fn format_message<R: Borrow<FluentResource>>(bundle: &FluentBundle<R>, id: &str) -> Option<String> {
+    let msg = bundle.get_message(id)?;
+    let mut errors = Vec::new();
+    let value = bundle.format_pattern(msg.value?, None, &mut errors);
+    if errors.is_empty() {
+        Some(value.into_owned())
+    } else {
+        log::warn!("failed to format message {}: {:?}", id, errors);
+        None
+    }
+}
+
+/// The first positional argument if it's a custom value of the type.
+fn positional_custom<'a, T: 'static>(positional: &'a [FluentValue]) -> Option<&'a T> {
+    match positional.first()? {
+        FluentValue::Custom(value) => (**value).as_any().downcast_ref(),
+        _ => None,
+    }
+}
+
+struct ListFormat {
+    separator: String,
+    and: String,
+}
+
+impl ListFormat {
+    fn format(&self, items: &[&str]) -> String {
+        match items.split_last() {
+            None => String::new(),
+            Some((last, [])) => (*last).to_owned(),
+            Some((last, rest)) => format!("{}{}{}", rest.join(&self.separator), self.and, last),
+        }
+    }
+}
+
+struct NumberFormat {
+    decimal_separator: String,
+    group_separator: String,
+}
+
+impl NumberFormat {
+    fn format(&self, value: f64, options: &fluent::types::FluentNumberOptions) -> String {
+        let max_fraction_digits = options
+            .maximum_fraction_digits
+            .unwrap_or(DEFAULT_MAX_FRACTION_DIGITS);
+        let min_fraction_digits = options
+            .minimum_fraction_digits
+            .unwrap_or(0)
+            .min(max_fraction_digits);
+
+        let formatted = format!("{:.*}", max_fraction_digits, value.abs());
+        let (int, fraction) = formatted.split_once('.').unwrap_or((&formatted, ""));
+        // drop trailing zeros that aren't required
+        let fraction_digits = fraction
+            .trim_end_matches('0')
+            .len()
+            .max(min_fraction_digits);
+        let fraction = &fraction[..fraction_digits];
+
+        let mut out = String::new();
+        if value.is_sign_negative() && value != 0.0 {
+            out.push('-');
+        }
+        for (i, digit) in int.chars().enumerate() {
+            if i > 0 && (int.len() - i) % 3 == 0 && options.use_grouping {
+                out.push_str(&self.group_separator);
+            }
+            out.push(digit);
+        }
+        if !fraction.is_empty() {
+            out.push_str(&self.decimal_separator);
+            out.push_str(fraction);
+        }
+        out
+    }
+}
+
+/// Messages used by the functions of a bundle.
+struct LocaleData {
+    list: ListFormat,
+    number: NumberFormat,
+    colors: HashMap<&'static str, String>,
+}
+
+impl LocaleData {
+    /// Resolve each message from the first bundle that has it.
+    fn resolve<R: Borrow<FluentResource>>(bundles: &[&FluentBundle<R>]) -> Self {
+        let message_or = |id: &str, default: &str| {
+            bundles
+                .iter()
+                .find_map(|bundle| format_message(bundle, id))
+                .unwrap_or_else(|| default.to_owned())
+        };
+
+        Self {
+            list: ListFormat {
+                separator: message_or("list-separator", ", "),
+                and: message_or("list-and", ", "),
+            },
+            number: NumberFormat {
+                decimal_separator: message_or("number-decimal-separator", "."),
+                group_separator: message_or("number-group-separator", ""),
+            },
+            colors: CARD_COLORS
+                .iter()
+                .map(|&color| (color, message_or(&format!("card-color-{}", color), color)))
+                .collect(),
+        }
+    }
+
+    fn add_to<R: Borrow<FluentResource>>(self, bundle: &mut FluentBundle<R>) {
+        let Self {
+            list,
+            number,
+            colors,
+        } = self;
+
+        let results = [
+            bundle.add_function("LIST", move |positional, _named| {
+                match positional_custom::<List>(positional) {
+                    Some(List(items)) => {
+                        let items: Vec<_> = items.iter().map(String::as_str).collect();
+                        list.format(&items).into()
+                    }
+                    None => FluentValue::None,
+                }
+            }),
+            bundle.add_function(
+                "NUMBER",
+                move |positional, named: &FluentArgs| match positional.first() {
+                    Some(FluentValue::Number(n)) => {
+                        let mut options = n.options.clone();
+                        options.merge(named);
+                        number.format(n.value, &options).into()
+                    }
+                    _ => FluentValue::None,
+                },
+            ),
+            bundle.add_function("CARD", move |positional, _named| {
+                match positional_custom::<CardInfo>(positional) {
+                    Some(card) => {
+                        let color = colors.get(card.color.as_str()).unwrap_or(&card.color);
+                        format!("{} {}", color, card.number).into()
+                    }
+                    None => FluentValue::None,
+                }
+            }),
+        ];
+        for res in results.iter() {
+            if let Err(err) = res {
+                log::warn!("failed to add function: {:?}", err);
+            }
+        }
+    }
+}
+
+/// Add the custom functions to the bundles.
+/// The messages they need are looked up in the bundle itself, then in the bundles after it
+/// and finally in the fallbacks.
+pub fn add_functions<R: Borrow<FluentResource>>(
+    bundles: &mut [FluentBundle<R>],
+    fallbacks: &[FluentBundle<R>],
+) {
+    let data: Vec<_> = (0..bundles.len())
+        .map(|i| {
+            let chain: Vec<_> = bundles[i..].iter().chain(fallbacks).collect();
+            LocaleData::resolve(&chain)
+        })
+        .collect();
+    for (bundle, data) in bundles.iter_mut().zip(data) {
+        data.add_to(bundle);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale::{
+        messages::{self, Message},
+        resource,
+    };
+
+    const POLISH: &str = r#"
+cards-drawn = { $name } { $count ->
+        [one] dobrał(a) kartę
+        [few] dobrał(a) { NUMBER($count) } karty
+       *[many] dobrał(a) { NUMBER($count) } kart
+    }
+card-played = { $name } zagrał(a) { CARD($card) }
+players-ready = { LIST($players) } { $count ->
+        [one] jest gotowy
+       *[other] są gotowi
+    }
+
+list-separator = {", "}
+list-and = {" i "}
+number-decimal-separator = ,
+number-group-separator = {"\u00A0"}
+card-color-red = czerwona
+"#;
+
+    fn new_bundle(langid: &str, source: &str) -> FluentBundle<FluentResource> {
+        let (resource, errors) = resource::parse_resource(source.to_owned());
+        assert!(errors.is_empty(), "{:?}", errors);
+        let mut bundle = resource::new_bundle(langid.parse().unwrap(), resource);
+        bundle.set_use_isolating(false);
+        add_functions(std::slice::from_mut(&mut bundle), &[]);
+        bundle
+    }
+
+    fn english() -> FluentBundle<FluentResource> {
+        new_bundle("en-GB", include_str!("../../site/locale/en-GB.ftl"))
+    }
+
+    fn german() -> FluentBundle<FluentResource> {
+        new_bundle("de-DE", include_str!("../../site/locale/de-DE.ftl"))
+    }
+
+    fn polish() -> FluentBundle<FluentResource> {
+        new_bundle("pl-PL", POLISH)
+    }
+
+    fn format(bundle: &FluentBundle<FluentResource>, message: &Message) -> String {
+        let msg = bundle.get_message(message.id()).unwrap();
+        let mut errors = Vec::new();
+        let value = bundle.format_pattern(msg.value.unwrap(), message.args(), &mut errors);
+        assert!(errors.is_empty(), "{:?}", errors);
+        value.into_owned()
+    }
+
+    fn players(names: &[&str]) -> Message<'static> {
+        let list: List = names.iter().copied().collect();
+        messages::players_ready(list, names.len())
+    }
+
+    #[test]
+    fn plurals_en() {
+        let bundle = english();
+        assert_eq!(
+            format(&bundle, &messages::cards_drawn("Alice", 1)),
+            "Alice drew a card"
+        );
+        assert_eq!(
+            format(&bundle, &messages::cards_drawn("Alice", 4)),
+            "Alice drew 4 cards"
+        );
+        assert_eq!(
+            format(&bundle, &messages::cards_drawn("Alice", 1234)),
+            "Alice drew 1,234 cards"
+        );
+    }
+
+    #[test]
+    fn plurals_pl() {
+        let bundle = polish();
+        let cases = [
+            (1, "Alice dobrał(a) kartę"),
+            (2, "Alice dobrał(a) 2 karty"),
+            (5, "Alice dobrał(a) 5 kart"),
+            (12, "Alice dobrał(a) 12 kart"),
+            (22, "Alice dobrał(a) 22 karty"),
+            (1234, "Alice dobrał(a) 1\u{a0}234 karty"),
+            (1235, "Alice dobrał(a) 1\u{a0}235 kart"),
+        ];
+        for (count, expected) in cases.iter() {
+            assert_eq!(
+                format(&bundle, &messages::cards_drawn("Alice", *count)),
+                *expected
+            );
+        }
+    }
+
+    #[test]
+    fn lists() {
+        let en = english();
+        assert_eq!(format(&en, &players(&["Bob"])), "Bob is ready");
+        assert_eq!(
+            format(&en, &players(&["Bob", "Carol"])),
+            "Bob and Carol are ready"
+        );
+        assert_eq!(
+            format(&en, &players(&["Bob", "Carol", "Dan"])),
+            "Bob, Carol and Dan are ready"
+        );
+
+        let pl = polish();
+        assert_eq!(
+            format(&pl, &players(&["Bob", "Carol", "Dan"])),
+            "Bob, Carol i Dan są gotowi"
+        );
+    }
+
+    #[test]
+    fn cards() {
+        let card = CardInfo {
+            color: String::from("red"),
+            number: 4,
+        };
+        assert_eq!(
+            format(&english(), &messages::card_played("Alice", &card)),
+            "Alice played red 4"
+        );
+        assert_eq!(
+            format(&german(), &messages::card_played("Alice", &card)),
+            "Alice hat Rot 4 gespielt"
+        );
+        assert_eq!(
+            format(&polish(), &messages::card_played("Alice", &card)),
+            "Alice zagrał(a) czerwona 4"
+        );
+    }
+
+    #[test]
+    fn values_without_function() {
+        let bundle = new_bundle("en-GB", "plain = { $players }: { $card }\n");
+        let mut args = FluentArgs::new();
+        let players: List = ["Bob", "Carol"].iter().copied().collect();
+        args.insert("players", players.into());
+        let card = CardInfo {
+            color: String::from("red"),
+            number: 4,
+        };
+        args.insert("card", (&card).into());
+
+        let msg = bundle.get_message("plain").unwrap();
+        let mut errors = Vec::new();
+        let value = bundle.format_pattern(msg.value.unwrap(), Some(&args), &mut errors);
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(value, "Bob, Carol: red 4");
+    }
+
+    #[test]
+    fn numbers() {
+        let german = NumberFormat {
+            decimal_separator: String::from(","),
+            group_separator: String::from("."),
+        };
+        let mut options = fluent::types::FluentNumberOptions::default();
+        assert_eq!(german.format(1234567.125, &options), "1.234.567,125");
+        assert_eq!(german.format(2.5, &options), "2,5");
+        assert_eq!(german.format(-1000.0, &options), "-1.000");
+
+        options.minimum_fraction_digits = Some(2);
+        assert_eq!(german.format(3.0, &options), "3,00");
+
+        options.maximum_fraction_digits = Some(2);
+        assert_eq!(german.format(2.0 / 3.0, &options), "0,67");
+
+        options.use_grouping = false;
+        assert_eq!(german.format(1234.0, &options), "1234,00");
+    }
+}
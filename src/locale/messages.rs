@@ -0,0 +1,38 @@
+//! Typed messages generated from the fallback locale.
+//!
+//! Every message without variables is a constant, messages with variables are functions
+//! taking each variable as an argument.
+//! Referencing a message that doesn't exist or passing the wrong arguments is a compile error.
+
+// not every message is used by the code
+#![allow(dead_code)]
+
+use fluent::FluentArgs;
+
+pub struct Message<'a> {
+    id: &'static str,
+    args: Option<FluentArgs<'a>>,
+}
+
+impl<'a> Message<'a> {
+    const fn new(id: &'static str) -> Self {
+        Self { id, args: None }
+    }
+
+    fn with_args(id: &'static str, args: FluentArgs<'a>) -> Self {
+        Self {
+            id,
+            args: Some(args),
+        }
+    }
+
+    pub fn id(&self) -> &'static str {
+        self.id
+    }
+
+    pub fn args(&self) -> Option<&FluentArgs<'a>> {
+        self.args.as_ref()
+    }
+}
+
+include!(concat!(env!("OUT_DIR"), "/messages.rs"));
@@ -0,0 +1,783 @@
+mod embedded;
+pub mod functions;
+pub mod messages;
+pub mod overlay;
+pub mod pseudo;
+mod resource;
+
+use crate::{
+    fetch::{self, AbortHandle, Client, ResponseCache, StorageStore},
+    storage::{Key, Storage},
+};
+use fluent::{FluentArgs, FluentBundle, FluentError, FluentMessage, FluentResource};
+use futures::future;
+use messages::Message;
+pub use resource::ParseError;
+use std::{
+    borrow::{Borrow, Cow},
+    collections::BTreeMap,
+    rc::Rc,
+    str::FromStr,
+    time::Duration,
+};
+use thiserror::Error;
+use unic_langid::{langid, CharacterDirection, LanguageIdentifier};
+
+static FALLBACK_LANGUAGE: LanguageIdentifier = langid!("en-GB");
+const MANIFEST_TIMEOUT: Duration = Duration::from_secs(5);
+/// How long cached resources are used before checking the server for changes.
+const RESOURCE_MAX_AGE: Duration = Duration::from_secs(60 * 60);
+
+fn get_browser_languages() -> Vec<LanguageIdentifier> {
+    let navigator = web_sys::window().unwrap().navigator();
+    navigator
+        .languages()
+        .iter()
+        .filter_map(|lang_val| {
+            let lang = lang_val.as_string()?;
+            match LanguageIdentifier::from_str(&lang) {
+                Ok(id) => Some(id),
+                Err(e) => {
+                    log::error!("failed to parse language `{}`: {}", lang, e);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+const LANGUAGE_KEY: Key<String> = Key::new("language");
+const BIDI_ISOLATION_KEY: Key<bool> = Key::new("bidi-isolation");
+
+pub fn get_stored_language() -> Option<LanguageIdentifier> {
+    let lang = Storage::local().load(&LANGUAGE_KEY)?;
+    LanguageIdentifier::from_str(&lang).ok()
+}
+
+/// Store the language chosen by the user.
+/// Passing `None` removes the choice, causing the browser languages to be used again.
+pub fn set_stored_language(langid: Option<&LanguageIdentifier>) {
+    let storage = Storage::local();
+    let res = match langid {
+        Some(langid) => storage.set(&LANGUAGE_KEY, &langid.to_string()),
+        None => storage.remove(&LANGUAGE_KEY),
+    };
+    if let Err(e) = res {
+        log::error!("failed to store language: {}", e);
+    }
+}
+
+/// Whether placeables such as player names are isolated from the surrounding text.
+/// Isolation stops names written in another direction from scrambling the message, but
+/// some fonts render the isolation marks as boxes so it can be turned off.
+pub fn get_stored_bidi_isolation() -> bool {
+    Storage::local().load(&BIDI_ISOLATION_KEY).unwrap_or(true)
+}
+
+pub fn set_stored_bidi_isolation(enabled: bool) {
+    if let Err(e) = Storage::local().set(&BIDI_ISOLATION_KEY, &enabled) {
+        log::error!("failed to store bidi isolation: {}", e);
+    }
+}
+
+fn get_user_languages() -> Vec<LanguageIdentifier> {
+    if let Some(lang) = get_stored_language() {
+        vec![lang]
+    } else {
+        get_browser_languages()
+    }
+}
+
+/// Client caching the resources in the app's storage.
+/// Cached resources are used right away even if they're stale, [`Client::revalidate`] tells
+/// whether the locale needs to be loaded again to pick up changes.
+pub fn caching_client() -> Client {
+    let cache = ResponseCache::new(StorageStore, RESOURCE_MAX_AGE).stale_while_revalidate();
+    Client::default().with_cache(cache)
+}
+
+/// Everything about the user that decides how their locale is loaded.
+#[derive(Clone, Debug, Default)]
+pub struct UserPreferences {
+    /// Requested languages, most preferred first.
+    pub languages: Vec<LanguageIdentifier>,
+    pub pseudo: bool,
+    pub bidi_isolation: bool,
+    pub overlay: Option<overlay::Overlay>,
+}
+
+impl UserPreferences {
+    /// Preferences stored by the user, falling back to the browser's languages.
+    pub fn from_browser() -> Self {
+        let languages = get_user_languages();
+        let pseudo = pseudo::is_requested_by_url() || languages.contains(&pseudo::PSEUDO_LANGUAGE);
+        Self {
+            languages,
+            pseudo,
+            bidi_isolation: get_stored_bidi_isolation(),
+            overlay: overlay::Overlay::get_stored(),
+        }
+    }
+}
+
+/// Expand a language into increasingly generic candidates.
+/// For example `zh-Hant-TW` expands to `zh-Hant-TW`, `zh-Hant` and `zh-TW`.
+/// The language-only candidate (`zh`) is only included if it was requested as such.
+fn expand_language(langid: &LanguageIdentifier) -> Vec<LanguageIdentifier> {
+    let mut without_variants = langid.clone();
+    without_variants.clear_variants();
+    let mut without_region = without_variants.clone();
+    without_region.clear_region();
+    let mut without_script = without_variants.clone();
+    without_script.clear_script();
+
+    let mut candidates = vec![langid.clone()];
+    for candidate in [without_variants, without_region, without_script] {
+        let is_language_only = candidate.script().is_none() && candidate.region().is_none();
+        if !is_language_only && !candidates.contains(&candidate) {
+            candidates.push(candidate);
+        }
+    }
+
+    candidates
+}
+
+/// Negotiate the chain of languages to load from the ones the user requested and the
+/// ones that are available.
+///
+/// Each requested language contributes its exact and truncated matches, followed by
+/// other regional variants of the same language and finally the language on its own.
+/// For a user requesting `fr-CA` this produces a chain like `fr-CA > fr-FR > fr`.
+/// The fallback language always comes last.
+fn negotiate_languages(
+    requested: &[LanguageIdentifier],
+    available: &[LanguageIdentifier],
+    fallback: &LanguageIdentifier,
+) -> Vec<LanguageIdentifier> {
+    let mut chain = Vec::new();
+    let mut add = |langid: &LanguageIdentifier| {
+        if !chain.contains(langid) {
+            chain.push(langid.clone());
+        }
+    };
+
+    for requested in requested {
+        for candidate in expand_language(requested) {
+            if available.contains(&candidate) {
+                add(&candidate);
+            }
+        }
+
+        let regional_variants = available.iter().filter(|langid| {
+            langid.language() == requested.language()
+                && langid.region().is_some()
+                && (requested.script().is_none()
+                    || langid.script().is_none()
+                    || langid.script() == requested.script())
+        });
+        for langid in regional_variants {
+            add(langid);
+        }
+
+        let mut language_only = requested.clone();
+        language_only.clear_script();
+        language_only.clear_region();
+        language_only.clear_variants();
+        if available.contains(&language_only) {
+            add(&language_only);
+        }
+    }
+
+    add(fallback);
+    chain
+}
+
+#[derive(Debug, Error)]
+pub enum FetchFluentError {
+    #[error(transparent)]
+    Fetch(#[from] fetch::FetchError),
+    #[error("failed to parse resource: {}", format_parse_errors(.0))]
+    Parse(Vec<ParseError>),
+}
+
+fn format_parse_errors(errors: &[ParseError]) -> String {
+    errors
+        .iter()
+        .map(ParseError::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AvailableLanguage {
+    pub langid: LanguageIdentifier,
+    /// Name of the language in the language itself.
+    pub name: String,
+}
+
+/// Fetch the manifest of all languages available on the server.
+async fn fetch_manifest(
+    client: &Client,
+    abort: &AbortHandle,
+) -> Result<Vec<AvailableLanguage>, fetch::FetchError> {
+    // a missing manifest isn't fatal, don't hold up the locale for too long
+    let mut request = client
+        .get("locale/index.json")
+        .timeout(Some(MANIFEST_TIMEOUT))
+        .retries(0)
+        .abort_with(abort)
+        .accept_json();
+    if let Some(manifest) = embedded::manifest() {
+        request = request.placeholder(manifest);
+    }
+    let manifest: BTreeMap<String, String> = request.json().await?;
+
+    let languages = manifest
+        .into_iter()
+        .filter_map(|(lang, name)| match LanguageIdentifier::from_str(&lang) {
+            Ok(langid) => Some(AvailableLanguage { langid, name }),
+            Err(e) => {
+                log::error!("failed to parse available language `{}`: {}", lang, e);
+                None
+            }
+        })
+        .collect();
+    Ok(languages)
+}
+
+/// Load all available languages.
+/// These are the embedded languages followed by any extra languages offered by the server.
+/// Failing to fetch the manifest is only an error if there are no embedded languages.
+pub async fn load_available_languages(
+    client: &Client,
+    abort: &AbortHandle,
+) -> Result<Vec<AvailableLanguage>, fetch::FetchError> {
+    let mut languages: Vec<_> = embedded::LOCALES
+        .iter()
+        .filter_map(|locale| {
+            let langid = LanguageIdentifier::from_str(locale.id).ok()?;
+            Some(AvailableLanguage {
+                langid,
+                name: locale.name.to_owned(),
+            })
+        })
+        .collect();
+
+    match fetch_manifest(client, abort).await {
+        Ok(fetched) => {
+            for lang in fetched {
+                if !languages.iter().any(|known| known.langid == lang.langid) {
+                    languages.push(lang);
+                }
+            }
+        }
+        Err(err) if !languages.is_empty() => {
+            log::warn!("failed to fetch locale manifest: {}", err);
+        }
+        Err(err) => return Err(err),
+    }
+
+    Ok(languages)
+}
+
+/// Parse the resource for the language.
+/// Broken entries are logged and skipped, only a resource without any usable entries is an error.
+fn parse_fluent_resource(
+    langid: &LanguageIdentifier,
+    source: String,
+) -> Result<FluentResource, FetchFluentError> {
+    let (resource, errors) = resource::parse_resource(source);
+    if errors.is_empty() {
+        return Ok(resource);
+    }
+
+    if !resource::has_entries(&resource) {
+        return Err(FetchFluentError::Parse(errors));
+    }
+
+    for err in &errors {
+        log::error!("{}: skipping broken entry: {}", langid, err);
+    }
+    Ok(resource)
+}
+
+/// Load the resource for the language from the server.
+/// Embedded resources stand in for the server's version until it's known, so a caching
+/// client can start with them right away.
+async fn load_fluent_resource(
+    client: &Client,
+    langid: &LanguageIdentifier,
+) -> Result<FluentResource, FetchFluentError> {
+    let mut request = client.get(format!("locale/{}.ftl", langid));
+    if let Some(locale) = embedded::get(langid) {
+        request = request.placeholder(locale.source);
+    }
+    let raw = request.text().await?;
+    parse_fluent_resource(langid, raw)
+}
+
+async fn load_fluent_bundle(
+    client: &Client,
+    langid: LanguageIdentifier,
+) -> Result<FluentBundle<FluentResource>, FetchFluentError> {
+    let resource = load_fluent_resource(client, &langid).await?;
+    Ok(resource::new_bundle(langid, resource))
+}
+
+#[derive(Debug, Error)]
+pub enum FluentFormatError {
+    #[error("message not found")]
+    NotFound,
+    #[error("message has no value")]
+    NoValue,
+
+    #[error("formatting error: {0:?}")]
+    Format(Vec<FluentError>),
+}
+
+pub fn format_message<'a>(
+    bundle: &'a FluentBundle<impl Borrow<FluentResource>>,
+    msg: &FluentMessage<'a>,
+    args: Option<&'a FluentArgs>,
+) -> Result<Cow<'a, str>, FluentFormatError> {
+    let pattern = msg.value.ok_or(FluentFormatError::NoValue)?;
+    let mut errors = Vec::new();
+    let s = bundle.format_pattern(pattern, args, &mut errors);
+    if errors.is_empty() {
+        Ok(s)
+    } else {
+        Err(FluentFormatError::Format(errors))
+    }
+}
+
+pub struct LocaleBundles<R> {
+    bundles: Vec<FluentBundle<R>>,
+    pseudo: bool,
+}
+
+impl LocaleBundles<FluentResource> {
+    /// Load the bundles for all languages negotiated from the requested ones.
+    /// Bundles that fail to load are skipped, except for the fallback which is required.
+    pub async fn load_negotiated(
+        client: &Client,
+        langids: impl IntoIterator<Item = LanguageIdentifier>,
+        fallback: LanguageIdentifier,
+    ) -> Result<Self, FetchFluentError> {
+        let requested: Vec<_> = langids.into_iter().collect();
+        let available = match load_available_languages(client, &AbortHandle::default()).await {
+            Ok(languages) => languages.into_iter().map(|lang| lang.langid).collect(),
+            Err(err) => {
+                log::warn!("failed to load available languages: {}", err);
+                requested.clone()
+            }
+        };
+        let chain = negotiate_languages(&requested, &available, &fallback);
+        log::debug!("negotiated language chain: {:?}", chain);
+
+        let results = future::join_all(chain.into_iter().map(|langid| async {
+            let is_fallback = langid == fallback;
+            (
+                is_fallback,
+                langid.clone(),
+                load_fluent_bundle(client, langid).await,
+            )
+        }))
+        .await;
+
+        let mut bundles = Vec::with_capacity(results.len());
+        for (is_fallback, langid, res) in results {
+            match res {
+                Ok(bundle) => bundles.push(bundle),
+                Err(err) if is_fallback => return Err(err),
+                Err(err) => log::warn!("failed to load language {}: {}", langid, err),
+            }
+        }
+
+        Ok(Self::new(bundles))
+    }
+}
+
+impl<R: Borrow<FluentResource>> LocaleBundles<R> {
+    fn new(mut bundles: Vec<FluentBundle<R>>) -> Self {
+        functions::add_functions(&mut bundles, &[]);
+        Self {
+            bundles,
+            pseudo: false,
+        }
+    }
+
+    /// Bundles layered on top of others, like a user's overlay of the built-in messages.
+    /// Functions use the fallback's locale data unless the bundles define their own.
+    fn with_fallback(mut bundles: Vec<FluentBundle<R>>, fallback: &Self) -> Self {
+        functions::add_functions(&mut bundles, &fallback.bundles);
+        Self {
+            bundles,
+            pseudo: false,
+        }
+    }
+
+    /// Whether placeables are wrapped in Unicode isolation marks, which is the default.
+    pub fn set_use_isolating(&mut self, use_isolating: bool) {
+        for bundle in &mut self.bundles {
+            bundle.set_use_isolating(use_isolating);
+        }
+    }
+
+    /// Pseudo-localize all messages formatted from now on.
+    pub fn enable_pseudo(&mut self) {
+        for bundle in &mut self.bundles {
+            bundle.set_transform(Some(pseudo::transform));
+        }
+        self.pseudo = true;
+    }
+
+    fn iter_bundles(&self) -> impl Iterator<Item = &FluentBundle<R>> {
+        self.bundles.iter()
+    }
+
+    fn iter_locales(&self) -> impl Iterator<Item = &LanguageIdentifier> {
+        self.iter_bundles()
+            .flat_map(move |bundle| bundle.locales.iter())
+    }
+
+    /// Language of the first bundle.
+    fn language(&self) -> &LanguageIdentifier {
+        self.iter_locales().next().unwrap_or(&FALLBACK_LANGUAGE)
+    }
+
+    fn iter_bundles_message<'a>(
+        &'a self,
+        id: &'a str,
+    ) -> impl Iterator<Item = (&'a FluentBundle<R>, FluentMessage<'a>)> {
+        self.iter_bundles()
+            .filter_map(move |bundle| bundle.get_message(id).map(|msg| (bundle, msg)))
+    }
+
+    pub fn format<'a>(
+        &'a self,
+        id: &'a str,
+        args: Option<&'a FluentArgs>,
+    ) -> Result<Cow<'a, str>, FluentFormatError> {
+        let (bundle, msg) = self
+            .iter_bundles_message(id)
+            .next()
+            .ok_or(FluentFormatError::NotFound)?;
+        let formatted = format_message(bundle, &msg, args)?;
+        if self.pseudo {
+            Ok(pseudo::wrap(&formatted).into())
+        } else {
+            Ok(formatted)
+        }
+    }
+
+    pub fn format_or_id<'a>(&'a self, id: &'a str, args: Option<&'a FluentArgs>) -> Cow<'a, str> {
+        self.format(id, args).unwrap_or_else(|err| {
+            log::warn!("failed to format message {}: {}", id, err);
+            id.into()
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct Locale {
+    builtin: Rc<LocaleBundles<FluentResource>>,
+    /// Messages supplied by the user, these take priority over the built-in ones.
+    overlay: Option<Rc<LocaleBundles<FluentResource>>>,
+}
+
+impl Locale {
+    fn new(
+        builtin: LocaleBundles<FluentResource>,
+        overlay: Option<LocaleBundles<FluentResource>>,
+    ) -> Self {
+        Self {
+            builtin: Rc::new(builtin),
+            overlay: overlay.map(Rc::new),
+        }
+    }
+
+    /// Bundle for the overlay in the primary language of the built-in bundles.
+    fn load_overlay(
+        builtin: &LocaleBundles<FluentResource>,
+        overlay: overlay::Overlay,
+    ) -> Option<LocaleBundles<FluentResource>> {
+        let langid = builtin.language().clone();
+        match parse_fluent_resource(&langid, overlay.source) {
+            Ok(resource) => {
+                let bundle = resource::new_bundle(langid, resource);
+                Some(LocaleBundles::with_fallback(vec![bundle], builtin))
+            }
+            Err(err) => {
+                log::error!("failed to load overlay {}: {}", overlay.name, err);
+                None
+            }
+        }
+    }
+
+    /// Load the locale for the preferences, fetching resources with the client.
+    pub async fn load(prefs: UserPreferences, client: &Client) -> Result<Self, FetchFluentError> {
+        let mut builtin =
+            LocaleBundles::load_negotiated(client, prefs.languages, FALLBACK_LANGUAGE.clone())
+                .await?;
+        let mut overlay = prefs
+            .overlay
+            .and_then(|overlay| Self::load_overlay(&builtin, overlay));
+
+        for bundles in std::iter::once(&mut builtin).chain(&mut overlay) {
+            bundles.set_use_isolating(prefs.bidi_isolation);
+            if prefs.pseudo {
+                bundles.enable_pseudo();
+            }
+        }
+        Ok(Self::new(builtin, overlay))
+    }
+
+    pub async fn load_for_user(client: &Client) -> Result<Self, FetchFluentError> {
+        Self::load(UserPreferences::from_browser(), client).await
+    }
+
+    /// Language the messages are primarily shown in.
+    pub fn language(&self) -> &LanguageIdentifier {
+        self.builtin.language()
+    }
+
+    pub fn direction(&self) -> CharacterDirection {
+        self.language().character_direction()
+    }
+
+    /// Set the language and writing direction of the document to match the locale.
+    pub fn apply_to_document(&self) {
+        let root = match web_sys::window()
+            .and_then(|window| window.document())
+            .and_then(|document| document.document_element())
+        {
+            Some(root) => root,
+            None => return,
+        };
+
+        let dir = match self.direction() {
+            CharacterDirection::LTR => "ltr",
+            CharacterDirection::RTL => "rtl",
+        };
+        let res = root
+            .set_attribute("lang", &self.language().to_string())
+            .and_then(|_| root.set_attribute("dir", dir));
+        if let Err(e) = res {
+            log::error!("failed to apply locale to document: {:?}", e);
+        }
+    }
+
+    pub fn localize<'a>(&'a self, message: &'a Message<'a>) -> Cow<'a, str> {
+        if let Some(overlay) = &self.overlay {
+            match overlay.format(message.id(), message.args()) {
+                Ok(formatted) => return formatted,
+                Err(FluentFormatError::NotFound) => {}
+                Err(err) => {
+                    log::warn!("failed to format overlay message {}: {}", message.id(), err)
+                }
+            }
+        }
+        self.builtin.format_or_id(message.id(), message.args())
+    }
+}
+
+impl PartialEq for Locale {
+    fn eq(&self, other: &Self) -> bool {
+        let overlay_eq = match (&self.overlay, &other.overlay) {
+            (Some(a), Some(b)) => Rc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        };
+        Rc::ptr_eq(&self.builtin, &other.builtin) && overlay_eq
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fetch::{
+        mock::{MemoryStore, MockReply, MockTransport},
+        Transport,
+    };
+    use futures::executor::block_on;
+
+    const MANIFEST: &str = r#"{"en-GB": "English", "fr-FR": "Français"}"#;
+    const FRENCH: &str = "title = Hyo en français\n";
+
+    fn transport() -> MockTransport {
+        MockTransport::new().get(
+            "locale/en-GB.ftl",
+            include_str!("../../site/locale/en-GB.ftl"),
+        )
+    }
+
+    fn load(transport: &Rc<MockTransport>, languages: &[&str]) -> Locale {
+        let prefs = UserPreferences {
+            languages: languages.iter().map(|lang| lang.parse().unwrap()).collect(),
+            ..UserPreferences::default()
+        };
+        let client = Client::new(Rc::clone(transport) as Rc<dyn Transport>);
+        block_on(Locale::load(prefs, &client)).unwrap()
+    }
+
+    fn langids(ids: &[&str]) -> Vec<LanguageIdentifier> {
+        ids.iter().map(|id| id.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn expands_languages() {
+        let cases: &[(&str, &[&str])] = &[
+            ("de", &["de"]),
+            ("de-CH", &["de-CH"]),
+            ("zh-Hant-TW", &["zh-Hant-TW", "zh-Hant", "zh-TW"]),
+            ("sr-Latn", &["sr-Latn"]),
+            ("ca-ES-valencia", &["ca-ES-valencia", "ca-ES"]),
+        ];
+        for (langid, expected) in cases {
+            assert_eq!(
+                expand_language(&langid.parse().unwrap()),
+                langids(expected),
+                "expanding {}",
+                langid
+            );
+        }
+    }
+
+    #[test]
+    fn negotiates_languages() {
+        let available = langids(&[
+            "en-GB",
+            "de",
+            "de-DE",
+            "fr-FR",
+            "fr-CA",
+            "zh-Hans",
+            "zh-Hant-TW",
+        ]);
+        let cases: &[(&[&str], &[&str])] = &[
+            // nothing requested or available
+            (&[], &["en-GB"]),
+            (&["ja-JP"], &["en-GB"]),
+            // region fallback
+            (&["de-CH"], &["de-DE", "de", "en-GB"]),
+            (&["de-DE"], &["de-DE", "de", "en-GB"]),
+            (&["de"], &["de", "de-DE", "en-GB"]),
+            (&["fr-CA"], &["fr-CA", "fr-FR", "en-GB"]),
+            // script subtags
+            (&["zh-Hant-HK"], &["zh-Hant-TW", "en-GB"]),
+            // a different script isn't a regional variant
+            (&["zh-Hans-CN"], &["zh-Hans", "en-GB"]),
+            // requested languages keep their order
+            (
+                &["fr-FR", "de-AT"],
+                &["fr-FR", "fr-CA", "de-DE", "de", "en-GB"],
+            ),
+            (
+                &["de-AT", "fr-FR"],
+                &["de-DE", "de", "fr-FR", "fr-CA", "en-GB"],
+            ),
+            // the fallback isn't repeated
+            (&["en-US"], &["en-GB"]),
+        ];
+        for (requested, expected) in cases {
+            assert_eq!(
+                negotiate_languages(&langids(requested), &available, &FALLBACK_LANGUAGE),
+                langids(expected),
+                "negotiating {:?}",
+                requested
+            );
+        }
+    }
+
+    #[test]
+    fn fetches_negotiated_languages() {
+        let transport = Rc::new(
+            transport()
+                .get("locale/index.json", MANIFEST)
+                .get("locale/fr-FR.ftl", FRENCH),
+        );
+        let locale = load(&transport, &["fr-CA"]);
+
+        assert_eq!(locale.language(), &langid!("fr-FR"));
+        assert_eq!(locale.localize(&messages::TITLE), "Hyo en français");
+        assert_eq!(locale.localize(&messages::BACK), "Back");
+    }
+
+    #[test]
+    fn tolerates_missing_manifest() {
+        let transport = Rc::new(transport());
+        let locale = load(&transport, &["en-GB"]);
+
+        assert_eq!(locale.localize(&messages::BACK), "Back");
+        assert_eq!(transport.count("locale/index.json"), 1);
+    }
+
+    #[test]
+    fn skips_unavailable_languages() {
+        let transport = Rc::new(transport().get("locale/index.json", MANIFEST).route(
+            "GET",
+            "locale/fr-FR.ftl",
+            vec![MockReply::network_error("offline")],
+        ));
+        let locale = load(&transport, &["fr-FR"]);
+
+        assert_eq!(locale.language(), &FALLBACK_LANGUAGE);
+        assert_eq!(locale.localize(&messages::TITLE), "Hyo");
+        // the initial attempt and two retries
+        assert_eq!(transport.count("locale/fr-FR.ftl"), 3);
+    }
+
+    #[test]
+    fn retries_server_errors() {
+        let transport = Rc::new(transport().get("locale/index.json", MANIFEST).route(
+            "GET",
+            "locale/fr-FR.ftl",
+            vec![MockReply::status(503, "busy"), MockReply::ok(FRENCH)],
+        ));
+        let locale = load(&transport, &["fr-FR"]);
+
+        assert_eq!(locale.localize(&messages::TITLE), "Hyo en français");
+        assert_eq!(transport.count("locale/fr-FR.ftl"), 2);
+    }
+
+    #[test]
+    fn gives_up_on_slow_manifest() {
+        let transport = Rc::new(transport().route(
+            "GET",
+            "locale/index.json",
+            vec![MockReply::ok(MANIFEST).with_latency(Duration::from_secs(60))],
+        ));
+        let locale = load(&transport, &["en-GB"]);
+
+        assert_eq!(locale.localize(&messages::BACK), "Back");
+        // a single attempt, it isn't retried
+        assert_eq!(transport.elapsed(), MANIFEST_TIMEOUT);
+        assert_eq!(transport.count("locale/index.json"), 1);
+    }
+
+    #[test]
+    fn starts_with_embedded_locales() {
+        let german = embedded::get(&langid!("de-DE"))
+            .unwrap()
+            .source
+            .replace("title = Hyo\n", "title = Hyo auf Deutsch\n");
+        let transport = Rc::new(transport().get("locale/de-DE.ftl", german));
+        let cache =
+            ResponseCache::new(MemoryStore::default(), RESOURCE_MAX_AGE).stale_while_revalidate();
+        let client = Client::new(Rc::clone(&transport) as Rc<dyn Transport>).with_cache(cache);
+        let prefs = UserPreferences {
+            languages: vec![langid!("de-DE")],
+            ..UserPreferences::default()
+        };
+
+        let locale = block_on(Locale::load(prefs.clone(), &client)).unwrap();
+        assert_eq!(locale.language(), &langid!("de-DE"));
+        assert_eq!(locale.localize(&messages::TITLE), "Hyo");
+        assert!(transport.requests().is_empty());
+
+        // the changed resource replaces the embedded one
+        assert!(block_on(client.revalidate()));
+        let locale = block_on(Locale::load(prefs, &client)).unwrap();
+        assert_eq!(locale.localize(&messages::TITLE), "Hyo auf Deutsch");
+    }
+}
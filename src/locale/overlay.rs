@@ -0,0 +1,121 @@
+//! Translations supplied by the user.
+//!
+//! An overlay is a Fluent resource whose messages take priority over the built-in ones.
+//! It's useful for trying out community translations or house-rule wording before they're
+//! part of the app.
+
+use super::{resource, ParseError};
+use crate::{
+    fetch::{AbortHandle, FetchError, RequestBuilder},
+    storage::{Key, Storage, StorageError},
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_futures::JsFuture;
+
+const OVERLAY_KEY: Key<Overlay> = Key::new("overlay");
+
+#[derive(Debug, Error)]
+pub enum OverlayError {
+    #[error(transparent)]
+    Fetch(#[from] FetchError),
+    #[error("failed to read file: {0:?}")]
+    Read(JsValue),
+    #[error("no usable messages: {}", super::format_parse_errors(.0))]
+    Parse(Vec<ParseError>),
+    #[error("failed to store overlay: {0}")]
+    Storage(#[from] StorageError),
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Overlay {
+    /// Where the overlay came from, either a file name or a URL.
+    pub name: String,
+    pub source: String,
+}
+
+impl Overlay {
+    /// Create an overlay from the source of a Fluent resource.
+    /// Fails if the resource doesn't contain a single usable message.
+    pub fn new(name: String, source: String) -> Result<Self, OverlayError> {
+        let (res, errors) = resource::parse_resource(source.clone());
+        if resource::has_messages(&res) {
+            Ok(Self { name, source })
+        } else {
+            Err(OverlayError::Parse(errors))
+        }
+    }
+
+    pub async fn read_file(file: web_sys::File) -> Result<Self, OverlayError> {
+        let text = JsFuture::from(file.text())
+            .await
+            .map_err(OverlayError::Read)?;
+        Self::new(file.name(), text.as_string().unwrap_or_default())
+    }
+
+    pub async fn fetch(url: &str, abort: &AbortHandle) -> Result<Self, OverlayError> {
+        let source = RequestBuilder::get(url).abort_with(abort).text().await?;
+        Self::new(url.to_owned(), source)
+    }
+
+    pub fn get_stored() -> Option<Self> {
+        Storage::local().load(&OVERLAY_KEY)
+    }
+
+    /// Store the overlay, replacing the previous one.
+    pub fn store(&self) -> Result<(), OverlayError> {
+        Ok(Storage::local().set(&OVERLAY_KEY, self)?)
+    }
+
+    pub fn remove_stored() {
+        if let Err(e) = Storage::local().remove(&OVERLAY_KEY) {
+            log::error!("failed to remove overlay: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale::{functions::List, messages, Locale, LocaleBundles};
+    use fluent::{FluentBundle, FluentResource};
+
+    fn new_bundle(source: &str) -> FluentBundle<FluentResource> {
+        let (res, _) = resource::parse_resource(source.to_owned());
+        let mut bundle = resource::new_bundle("en-GB".parse().unwrap(), res);
+        bundle.set_use_isolating(false);
+        bundle
+    }
+
+    #[test]
+    fn overlay_takes_priority() {
+        let builtin = LocaleBundles::new(vec![new_bundle(include_str!(
+            "../../site/locale/en-GB.ftl"
+        ))]);
+        let overlay = LocaleBundles::with_fallback(
+            vec![new_bundle(
+                "title = Hyo!\nplayers-ready = { LIST($players) } ready to play\n",
+            )],
+            &builtin,
+        );
+        let locale = Locale::new(builtin, Some(overlay));
+
+        assert_eq!(locale.localize(&messages::TITLE), "Hyo!");
+        assert_eq!(locale.localize(&messages::BACK), "Back");
+
+        let players: List = vec!["Bob", "Carol"].into_iter().collect();
+        assert_eq!(
+            locale.localize(&messages::players_ready(players, 2)),
+            "Bob and Carol ready to play"
+        );
+    }
+
+    #[test]
+    fn rejects_resources_without_messages() {
+        let res = Overlay::new(String::from("broken.ftl"), String::from("= nope"));
+        assert!(matches!(res, Err(OverlayError::Parse(_))));
+        let res = Overlay::new(String::from("terms.ftl"), String::from("-brand = Hyo\n"));
+        assert!(matches!(res, Err(OverlayError::Parse(_))));
+    }
+}
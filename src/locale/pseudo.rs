@@ -0,0 +1,110 @@
+//! Pseudo-localization for catching hard-coded strings and layouts that can't cope with
+//! longer text.
+//!
+//! Text is accented, expanded by roughly 40% and every message is wrapped in brackets.
+//! Only the text elements of a pattern are transformed so placeables stay intact.
+
+use std::borrow::Cow;
+use unic_langid::{langid, LanguageIdentifier};
+
+/// Language used to select pseudo-localization in the settings.
+pub static PSEUDO_LANGUAGE: LanguageIdentifier = langid!("en-XA");
+
+/// Name of the URL query parameter enabling pseudo-localization.
+const QUERY_PARAM: &str = "pseudo";
+
+const PLAIN: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const ACCENTED: &str = "ȧƀƈḓḗƒɠħīĵķŀḿƞǿƥɋřşŧŭṽẇẋẏẑȦƁƇḒḖƑƓĦĪĴĶĿḾȠǾƤɊŘŞŦŬṼẆẊẎẐ";
+
+fn accent(c: char) -> char {
+    PLAIN
+        .chars()
+        .position(|plain| plain == c)
+        .and_then(|i| ACCENTED.chars().nth(i))
+        .unwrap_or(c)
+}
+
+fn is_vowel(c: char) -> bool {
+    "aeiouAEIOU".contains(c)
+}
+
+/// Transform a text element.
+/// Vowels are doubled until the text is about 40% longer, any remaining expansion is
+/// padded with tildes.
+pub fn transform(text: &str) -> Cow<'_, str> {
+    if !text.chars().any(char::is_alphanumeric) {
+        return text.into();
+    }
+
+    let mut expansion = (text.chars().count() * 2).div_ceil(5);
+    let mut out = String::with_capacity(text.len() * 3);
+    for c in text.chars() {
+        let accented = accent(c);
+        out.push(accented);
+        if expansion > 0 && is_vowel(c) {
+            out.push(accented);
+            expansion -= 1;
+        }
+    }
+    out.extend(std::iter::repeat_n('~', expansion));
+    out.into()
+}
+
+/// Wrap a formatted message in brackets so truncated and concatenated strings stand out.
+pub fn wrap(message: &str) -> String {
+    format!("[{}]", message)
+}
+
+/// Whether pseudo-localization was requested through the URL, for example `?pseudo`.
+pub fn is_requested_by_url() -> bool {
+    let search = match web_sys::window().and_then(|window| window.location().search().ok()) {
+        Some(search) => search,
+        None => return false,
+    };
+
+    search
+        .trim_start_matches('?')
+        .split('&')
+        .any(|param| param.split('=').next() == Some(QUERY_PARAM))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale::{resource, LocaleBundles};
+    use fluent::FluentArgs;
+
+    #[test]
+    fn accents_letters() {
+        assert_eq!(PLAIN.chars().count(), ACCENTED.chars().count());
+        assert_eq!(transform("Hyo!"), "Ħẏǿǿ!~");
+    }
+
+    #[test]
+    fn expands_text() {
+        let text = "Join game";
+        let transformed = transform(text);
+        assert_eq!(
+            transformed.chars().count(),
+            text.chars().count() + (text.chars().count() * 2).div_ceil(5)
+        );
+    }
+
+    #[test]
+    fn keeps_whitespace() {
+        assert_eq!(transform(" "), " ");
+    }
+
+    #[test]
+    fn preserves_placeables() {
+        let (res, _) = resource::parse_resource("greeting = Hi { $name }\n".to_owned());
+        let bundle = resource::new_bundle(PSEUDO_LANGUAGE.clone(), res);
+        let mut bundles = LocaleBundles::new(vec![bundle]);
+        bundles.enable_pseudo();
+
+        let mut args = FluentArgs::new();
+        args.insert("name", "Alice".into());
+        let formatted = bundles.format("greeting", Some(&args)).unwrap();
+        assert_eq!(formatted, "[Ħīī ~\u{2068}Alice\u{2069}]");
+    }
+}
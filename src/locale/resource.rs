@@ -0,0 +1,160 @@
+use fluent::{FluentBundle, FluentError, FluentResource};
+use fluent_syntax::{ast, parser::errors::ErrorKind};
+use std::{borrow::Borrow, fmt};
+use unic_langid::LanguageIdentifier;
+
+/// A syntax error in a Fluent resource.
+#[derive(Debug)]
+pub struct ParseError {
+    /// Id of the message or term that failed to parse, if it could be determined.
+    pub entry_id: Option<String>,
+    pub line: usize,
+    pub column: usize,
+    pub kind: ErrorKind,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}: {:?}", self.line, self.column, self.kind)?;
+        if let Some(id) = &self.entry_id {
+            write!(f, " in `{}`", id)?;
+        }
+        Ok(())
+    }
+}
+
+/// Id of the entry the junk was supposed to be.
+fn junk_entry_id(junk: &str) -> Option<String> {
+    let (id, _) = junk.split_once('=')?;
+    let id = id.trim().trim_start_matches('-');
+    let is_identifier = id.starts_with(|c: char| c.is_ascii_alphabetic())
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if is_identifier {
+        Some(id.to_owned())
+    } else {
+        None
+    }
+}
+
+/// Parse the source of a Fluent resource.
+///
+/// Entries that fail to parse are dropped while the rest of the resource is kept.
+/// The errors are returned along with the partial resource.
+pub fn parse_resource(source: String) -> (FluentResource, Vec<ParseError>) {
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+    let line_column = |offset: usize| {
+        let line = line_starts.partition_point(|&start| start <= offset);
+        let column = offset - line_starts[line - 1] + 1;
+        (line, column)
+    };
+
+    match FluentResource::try_new(source) {
+        Ok(resource) => (resource, Vec::new()),
+        Err((resource, errors)) => {
+            // the parser produces exactly one junk entry per error
+            let junk = resource.ast().body.iter().filter_map(|entry| match entry {
+                ast::ResourceEntry::Junk(junk) => Some(*junk),
+                _ => None,
+            });
+            let errors = errors
+                .into_iter()
+                .zip(junk.map(Some).chain(std::iter::repeat(None)))
+                .map(|(err, junk)| {
+                    let (line, column) = line_column(err.pos.0);
+                    ParseError {
+                        entry_id: junk.and_then(junk_entry_id),
+                        line,
+                        column,
+                        kind: err.kind,
+                    }
+                })
+                .collect();
+            (resource, errors)
+        }
+    }
+}
+
+/// Whether the resource contains at least one message or term.
+pub fn has_entries(resource: &FluentResource) -> bool {
+    resource.ast().body.iter().any(|entry| {
+        matches!(
+            entry,
+            ast::ResourceEntry::Entry(ast::Entry::Message(_))
+                | ast::ResourceEntry::Entry(ast::Entry::Term(_))
+        )
+    })
+}
+
+/// Whether the resource contains at least one message.
+/// Terms alone can't be shown, they're only used by messages.
+pub fn has_messages(resource: &FluentResource) -> bool {
+    resource
+        .ast()
+        .body
+        .iter()
+        .any(|entry| matches!(entry, ast::ResourceEntry::Entry(ast::Entry::Message(_))))
+}
+
+/// Create a bundle for the language containing the resource.
+/// Entries overriding earlier ones are ignored and logged.
+pub fn new_bundle<R: Borrow<FluentResource>>(
+    langid: LanguageIdentifier,
+    resource: R,
+) -> FluentBundle<R> {
+    let mut bundle = FluentBundle::new(std::slice::from_ref(&langid));
+    if let Err(errors) = bundle.add_resource(resource) {
+        for err in errors {
+            match err {
+                FluentError::Overriding { kind, id } => {
+                    log::warn!("{}: duplicate {} `{}` ignored", langid, kind, id)
+                }
+                err => log::warn!("{}: failed to add resource: {:?}", langid, err),
+            }
+        }
+    }
+    bundle
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_valid_entries() {
+        let source = "hello = Hello\nbroken = Hi { }\nbye = Bye\n";
+        let (resource, errors) = parse_resource(source.to_owned());
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].entry_id.as_deref(), Some("broken"));
+        assert_eq!((errors[0].line, errors[0].column), (2, 15));
+        assert!(has_entries(&resource));
+
+        let bundle = new_bundle("en-GB".parse().unwrap(), resource);
+        assert!(bundle.has_message("hello"));
+        assert!(bundle.has_message("bye"));
+        assert!(!bundle.has_message("broken"));
+    }
+
+    #[test]
+    fn reports_resources_without_entries() {
+        let (resource, errors) = parse_resource("= nothing\n".to_owned());
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].entry_id, None);
+        assert!(!has_entries(&resource));
+    }
+
+    #[test]
+    fn keeps_first_duplicate() {
+        let (resource, errors) = parse_resource("a = First\na = Second\n".to_owned());
+        assert!(errors.is_empty());
+
+        let bundle = new_bundle("en-GB".parse().unwrap(), resource);
+        let msg = bundle.get_message("a").unwrap();
+        let value = bundle.format_pattern(msg.value.unwrap(), None, &mut Vec::new());
+        assert_eq!(value, "First");
+    }
+}
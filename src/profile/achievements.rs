@@ -0,0 +1,238 @@
+//! Achievements unlocked by playing.
+//!
+//! Every achievement is a [`Condition`] over the events of a single match. The conditions
+//! are plain data so new achievements only need an entry in [`ACHIEVEMENTS`] and their
+//! messages.
+
+use crate::{
+    game::GameEvent,
+    locale::{messages, messages::Message},
+};
+use serde::{Deserialize, Serialize};
+
+/// Something that can be counted for a player in a match.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Counter {
+    CardsDrawn,
+    /// Cards of a number played.
+    PlaysOfNumber(u8),
+    HyoCalls,
+    MissedHyoCalls,
+    /// Most cards held at once.
+    MostHeld,
+}
+
+impl Counter {
+    fn count(self, player: usize, events: &[GameEvent]) -> u32 {
+        let mut count = 0;
+        let mut held = 0;
+        for event in events.iter().filter(|event| event.player() == player) {
+            match (self, event) {
+                (Self::CardsDrawn, GameEvent::Draw { count: drawn, .. }) => count += drawn,
+                (Self::HyoCalls, GameEvent::CallHyo { .. })
+                | (Self::MissedHyoCalls, GameEvent::MissHyo { .. }) => count += 1,
+                (Self::PlaysOfNumber(number), GameEvent::Play { card, .. })
+                    if card.number == number =>
+                {
+                    count += 1
+                }
+                (Self::MostHeld, GameEvent::Deal { count: dealt, .. })
+                | (Self::MostHeld, GameEvent::Draw { count: dealt, .. }) => {
+                    held += dealt;
+                    count = count.max(held);
+                }
+                (Self::MostHeld, GameEvent::Play { .. }) => held = held.saturating_sub(1),
+                _ => {}
+            }
+        }
+        count
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Condition {
+    /// The player finished first.
+    Won,
+    AtLeast(Counter, u32),
+    AtMost(Counter, u32),
+    All(&'static [Condition]),
+}
+
+impl Condition {
+    pub fn holds(&self, player: usize, events: &[GameEvent]) -> bool {
+        match *self {
+            Self::Won => events
+                .iter()
+                .find(|event| matches!(event, GameEvent::Finish { .. }))
+                .is_some_and(|event| event.player() == player),
+            Self::AtLeast(counter, n) => counter.count(player, events) >= n,
+            Self::AtMost(counter, n) => counter.count(player, events) <= n,
+            Self::All(conditions) => conditions
+                .iter()
+                .all(|condition| condition.holds(player, events)),
+        }
+    }
+}
+
+pub struct Achievement {
+    /// Stored in the profile, must never change.
+    pub id: &'static str,
+    pub name: Message<'static>,
+    pub description: Message<'static>,
+    pub condition: Condition,
+}
+
+pub const ACHIEVEMENTS: &[Achievement] = &[
+    Achievement {
+        id: "first-win",
+        name: messages::ACHIEVEMENT_FIRST_WIN,
+        description: messages::ACHIEVEMENT_FIRST_WIN_DESCRIPTION,
+        condition: Condition::Won,
+    },
+    Achievement {
+        id: "clean-hands",
+        name: messages::ACHIEVEMENT_CLEAN_HANDS,
+        description: messages::ACHIEVEMENT_CLEAN_HANDS_DESCRIPTION,
+        condition: Condition::All(&[Condition::Won, Condition::AtMost(Counter::CardsDrawn, 0)]),
+    },
+    Achievement {
+        id: "comeback",
+        name: messages::ACHIEVEMENT_COMEBACK,
+        description: messages::ACHIEVEMENT_COMEBACK_DESCRIPTION,
+        condition: Condition::All(&[Condition::Won, Condition::AtLeast(Counter::MostHeld, 20)]),
+    },
+    Achievement {
+        id: "lucky-sevens",
+        name: messages::ACHIEVEMENT_LUCKY_SEVENS,
+        description: messages::ACHIEVEMENT_LUCKY_SEVENS_DESCRIPTION,
+        condition: Condition::AtLeast(Counter::PlaysOfNumber(7), 4),
+    },
+    Achievement {
+        id: "loud-and-clear",
+        name: messages::ACHIEVEMENT_LOUD_AND_CLEAR,
+        description: messages::ACHIEVEMENT_LOUD_AND_CLEAR_DESCRIPTION,
+        condition: Condition::All(&[
+            Condition::Won,
+            Condition::AtLeast(Counter::HyoCalls, 1),
+            Condition::AtMost(Counter::MissedHyoCalls, 0),
+        ]),
+    },
+];
+
+/// Achievements earned by the player in a finished match.
+pub fn evaluate(
+    player: usize,
+    events: &[GameEvent],
+) -> impl Iterator<Item = &'static Achievement> + '_ {
+    ACHIEVEMENTS
+        .iter()
+        .filter(move |achievement| achievement.condition.holds(player, events))
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Unlocked {
+    pub id: String,
+    /// Milliseconds since the Unix epoch.
+    pub unlocked_at: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::card::CardInfo;
+
+    /// Events of a match as they're kept in a saved game.
+    fn recorded(json: &str) -> Vec<GameEvent> {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn earned(events: &[GameEvent]) -> Vec<&'static str> {
+        evaluate(0, events)
+            .map(|achievement| achievement.id)
+            .collect()
+    }
+
+    #[test]
+    fn ids_are_unique() {
+        for (i, achievement) in ACHIEVEMENTS.iter().enumerate() {
+            assert_eq!(
+                ACHIEVEMENTS[..i]
+                    .iter()
+                    .find(|a| a.id == achievement.id)
+                    .map(|a| a.id),
+                None
+            );
+        }
+    }
+
+    #[test]
+    fn won_without_drawing() {
+        let events = recorded(
+            r#"[
+                {"type": "deal", "player": 0, "count": 2},
+                {"type": "call-hyo", "player": 0},
+                {"type": "play", "player": 0, "card": {"color": "red", "number": 7}},
+                {"type": "play", "player": 0, "card": {"color": "red", "number": 3}},
+                {"type": "finish", "player": 0}
+            ]"#,
+        );
+        assert_eq!(
+            earned(&events),
+            ["first-win", "clean-hands", "loud-and-clear"]
+        );
+        assert_eq!(evaluate(1, &events).count(), 0);
+    }
+
+    #[test]
+    fn came_back_from_a_full_hand() {
+        let mut events = recorded(
+            r#"[
+                {"type": "deal", "player": 0, "count": 7},
+                {"type": "draw", "player": 0, "count": 13},
+                {"type": "miss-hyo", "player": 0}
+            ]"#,
+        );
+        for number in 0..20 {
+            events.insert(
+                2,
+                GameEvent::Play {
+                    player: 0,
+                    card: CardInfo {
+                        color: String::from("green"),
+                        number: number % 10,
+                    },
+                },
+            );
+        }
+        events.push(GameEvent::Finish { player: 0 });
+        assert_eq!(earned(&events), ["first-win", "comeback"]);
+
+        // drawing the same number of cards while playing some doesn't count
+        events.insert(
+            1,
+            GameEvent::Play {
+                player: 0,
+                card: CardInfo {
+                    color: String::from("blue"),
+                    number: 1,
+                },
+            },
+        );
+        assert_eq!(Counter::MostHeld.count(0, &events), 19);
+    }
+
+    #[test]
+    fn counts_plays_of_a_number() {
+        let events = recorded(
+            r#"[
+                {"type": "play", "player": 0, "card": {"color": "red", "number": 7}},
+                {"type": "play", "player": 1, "card": {"color": "blue", "number": 7}},
+                {"type": "play", "player": 0, "card": {"color": "blue", "number": 7}},
+                {"type": "play", "player": 0, "card": {"color": "green", "number": 7}},
+                {"type": "finish", "player": 1},
+                {"type": "play", "player": 0, "card": {"color": "yellow", "number": 7}}
+            ]"#,
+        );
+        assert_eq!(earned(&events), ["lucky-sevens"]);
+    }
+}
@@ -0,0 +1,93 @@
+//! Avatars generated from a seed.
+//!
+//! An avatar is a symmetric 5×5 pattern in a single color, like the identicons some sites
+//! use. The same seed always produces the same avatar so only the seed needs to be stored
+//! or sent to other players.
+
+pub const SIZE: u32 = 5;
+/// Columns that are generated, the rest mirror them.
+const HALF: u32 = SIZE.div_ceil(2);
+
+/// Scramble the bits of the seed, adapted from the `lowbias32` hash.
+fn mix(mut x: u32) -> u32 {
+    x ^= x >> 16;
+    x = x.wrapping_mul(0x7feb_352d);
+    x ^= x >> 15;
+    x = x.wrapping_mul(0x846c_a68b);
+    x ^= x >> 16;
+    x
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Avatar {
+    seed: u32,
+}
+
+impl Avatar {
+    pub fn new(seed: u32) -> Self {
+        Self { seed }
+    }
+
+    /// A set of avatars to choose from, starting at the seed.
+    pub fn choices(seed: u32, count: u32) -> impl Iterator<Item = Self> {
+        (0..count).map(move |i| Self::new(seed.wrapping_add(i)))
+    }
+
+    pub fn seed(self) -> u32 {
+        self.seed
+    }
+
+    fn hue(self) -> u32 {
+        mix(self.seed ^ 0x9e37_79b9) % 360
+    }
+
+    /// Filled cells as `(column, row)`.
+    fn cells(self) -> impl Iterator<Item = (u32, u32)> {
+        let bits = mix(self.seed);
+        (0..SIZE).flat_map(move |row| {
+            (0..SIZE).filter_map(move |col| {
+                let source_col = col.min(SIZE - 1 - col);
+                let bit = row * HALF + source_col;
+                if bits >> bit & 1 == 1 {
+                    Some((col, row))
+                } else {
+                    None
+                }
+            })
+        })
+    }
+
+    /// Color of the cells as a CSS value.
+    pub fn color(self) -> String {
+        format!("hsl({}, 70%, 60%)", self.hue())
+    }
+
+    /// SVG path of the cells, they're one unit wide.
+    pub fn path(self) -> String {
+        self.cells()
+            .map(|(col, row)| format!("M{},{}h1v1h-1z", col, row))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_deterministic() {
+        assert_eq!(Avatar::new(42).path(), Avatar::new(42).path());
+        assert_eq!(Avatar::new(42).color(), Avatar::new(42).color());
+        assert_ne!(Avatar::new(42).path(), Avatar::new(43).path());
+    }
+
+    #[test]
+    fn is_symmetric() {
+        for avatar in Avatar::choices(0, 100) {
+            let cells: Vec<_> = avatar.cells().collect();
+            for &(col, row) in &cells {
+                assert!(cells.contains(&(SIZE - 1 - col, row)), "{:?}", avatar);
+            }
+        }
+    }
+}
@@ -0,0 +1,235 @@
+//! The local player's profile.
+//!
+//! The profile is stored locally and a [`PlayerInfo`] made from it is what other players
+//! get to see.
+
+pub mod achievements;
+pub mod avatar;
+pub mod stats;
+
+use crate::{
+    locale::{messages, Locale},
+    storage::{Key, Storage, StorageError},
+};
+use achievements::{Achievement, Unlocked};
+pub use avatar::Avatar;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+use web_sys::HtmlElement;
+
+const PROFILE_KEY: Key<Profile> = Key::new("profile");
+/// Longest name in characters.
+pub const MAX_NAME_LENGTH: usize = 20;
+
+/// Color used for highlights in the UI, matching the card colors.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Accent {
+    Red,
+    #[default]
+    Blue,
+    Yellow,
+    Green,
+}
+
+impl Accent {
+    pub const ALL: [Self; 4] = [Self::Red, Self::Blue, Self::Yellow, Self::Green];
+
+    pub fn css_color(self) -> &'static str {
+        match self {
+            Self::Red => "#e53935",
+            Self::Blue => "#1e88e5",
+            Self::Yellow => "#fdd835",
+            Self::Green => "#43a047",
+        }
+    }
+
+    pub fn localize(self, locale: &Locale) -> String {
+        let message = match self {
+            Self::Red => messages::CARD_COLOR_RED,
+            Self::Blue => messages::CARD_COLOR_BLUE,
+            Self::Yellow => messages::CARD_COLOR_YELLOW,
+            Self::Green => messages::CARD_COLOR_GREEN,
+        };
+        locale.localize(&message).into_owned()
+    }
+}
+
+/// What other players see of someone.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PlayerInfo {
+    /// Empty if the player didn't choose a name.
+    pub name: String,
+    pub avatar: u32,
+    pub accent: Accent,
+}
+
+impl PlayerInfo {
+    /// Name to show, using a generic one if the player didn't choose any.
+    pub fn display_name(&self, locale: &Locale) -> String {
+        if self.name.is_empty() {
+            locale.localize(&messages::PLAYER_DEFAULT_NAME).into_owned()
+        } else {
+            self.name.clone()
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Profile {
+    pub name: String,
+    /// Seed of the avatar.
+    pub avatar: u32,
+    #[serde(default)]
+    pub accent: Accent,
+    #[serde(default)]
+    pub achievements: Vec<Unlocked>,
+}
+
+impl Profile {
+    /// A new profile with a random avatar.
+    fn new() -> Self {
+        Self {
+            name: String::new(),
+            avatar: (js_sys::Math::random() * f64::from(u32::MAX)) as u32,
+            accent: Accent::default(),
+            achievements: Vec::new(),
+        }
+    }
+
+    /// The stored profile, a new one is created and stored if there isn't any.
+    /// A stored profile that can't be read is backed up before it's replaced.
+    pub fn load(storage: &Storage) -> Self {
+        match storage.get(&PROFILE_KEY) {
+            Ok(Some(profile)) => return profile,
+            Ok(None) => {}
+            Err(err) => {
+                log::error!("failed to load profile, replacing it: {}", err);
+                if let Err(err) = storage.back_up(&PROFILE_KEY) {
+                    // keep the unreadable profile rather than losing it
+                    log::error!("failed to back up profile: {}", err);
+                    return Self::new();
+                }
+            }
+        }
+
+        let profile = Self::new();
+        if let Err(err) = profile.store(storage) {
+            log::warn!("failed to store new profile: {}", err);
+        }
+        profile
+    }
+
+    pub fn store(&self, storage: &Storage) -> Result<(), StorageError> {
+        storage.set(&PROFILE_KEY, self)
+    }
+
+    pub fn set_name(&mut self, name: &str) {
+        self.name = name.chars().take(MAX_NAME_LENGTH).collect();
+    }
+
+    pub fn has_unlocked(&self, achievement: &Achievement) -> bool {
+        self.achievements
+            .iter()
+            .any(|unlocked| unlocked.id == achievement.id)
+    }
+
+    /// Unlock the achievements, returning the ones that weren't unlocked before.
+    pub fn unlock(
+        &mut self,
+        achievements: impl IntoIterator<Item = &'static Achievement>,
+        now: f64,
+    ) -> Vec<&'static Achievement> {
+        let mut new = Vec::new();
+        for achievement in achievements {
+            if !self.has_unlocked(achievement) {
+                self.achievements.push(Unlocked {
+                    id: achievement.id.to_owned(),
+                    unlocked_at: now,
+                });
+                new.push(achievement);
+            }
+        }
+        new
+    }
+
+    pub fn player_info(&self) -> PlayerInfo {
+        PlayerInfo {
+            name: self.name.trim().to_owned(),
+            avatar: self.avatar,
+            accent: self.accent,
+        }
+    }
+
+    /// Use the accent color throughout the document.
+    pub fn apply_to_document(&self) {
+        let root = match web_sys::window()
+            .and_then(|window| window.document())
+            .and_then(|document| document.document_element())
+            .and_then(|root| root.dyn_into::<HtmlElement>().ok())
+        {
+            Some(root) => root,
+            None => return,
+        };
+
+        if let Err(e) = root
+            .style()
+            .set_property("--accent", self.accent.css_color())
+        {
+            log::error!("failed to apply profile to document: {:?}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile() -> Profile {
+        Profile {
+            name: String::from(" Alice "),
+            avatar: 7,
+            accent: Accent::Green,
+            achievements: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn stores_profile() {
+        let storage = Storage::memory();
+        profile().store(&storage).unwrap();
+        assert_eq!(Profile::load(&storage), profile());
+    }
+
+    #[test]
+    fn unlocks_achievements_once() {
+        let mut profile = profile();
+        let first_win = &achievements::ACHIEVEMENTS[0];
+        let comeback = &achievements::ACHIEVEMENTS[2];
+
+        let new = profile.unlock(vec![first_win], 1.0);
+        assert_eq!(new.iter().map(|a| a.id).collect::<Vec<_>>(), ["first-win"]);
+        let new = profile.unlock(vec![first_win, comeback], 2.0);
+        assert_eq!(new.iter().map(|a| a.id).collect::<Vec<_>>(), ["comeback"]);
+
+        assert!(profile.has_unlocked(first_win));
+        assert_eq!(profile.achievements[0].unlocked_at, 1.0);
+    }
+
+    #[test]
+    fn limits_name_length() {
+        let mut profile = profile();
+        profile.set_name(&"ö".repeat(30));
+        assert_eq!(profile.name.chars().count(), MAX_NAME_LENGTH);
+    }
+
+    #[test]
+    fn shares_trimmed_name() {
+        let info = profile().player_info();
+        assert_eq!(info.name, "Alice");
+        assert_eq!(
+            serde_json::to_string(&info).unwrap(),
+            r#"{"name":"Alice","avatar":7,"accent":"green"}"#
+        );
+    }
+}
@@ -0,0 +1,301 @@
+//! Lifetime statistics of the local player.
+//!
+//! Every finished match is summarized from its events and added to the totals. Only the
+//! most recent matches are kept in full, the totals cover every match ever played.
+
+use crate::{
+    components::card::CardInfo,
+    game::GameEvent,
+    storage::{Key, Storage, StorageError},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+const STATS_KEY: Key<Stats> = Key::new("stats");
+/// Number of matches kept in the history.
+pub const HISTORY_LENGTH: usize = 20;
+
+/// Outcome of a single match for one player.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct MatchSummary {
+    /// Milliseconds since the Unix epoch.
+    pub finished_at: f64,
+    pub players: usize,
+    /// Finishing position starting at 1.
+    pub position: usize,
+    pub cards_drawn: u32,
+    pub cards_played: u32,
+    pub missed_hyo_calls: u32,
+}
+
+impl MatchSummary {
+    /// Summarize a match for the player with the given index.
+    /// Players who didn't finish share the last position.
+    pub fn new(player: usize, players: usize, events: &[GameEvent], finished_at: f64) -> Self {
+        let mut summary = Self {
+            finished_at,
+            players: players.max(1),
+            position: players.max(1),
+            cards_drawn: 0,
+            cards_played: 0,
+            missed_hyo_calls: 0,
+        };
+
+        let mut finished = 0;
+        for event in events {
+            match *event {
+                GameEvent::Finish { player: p } => {
+                    finished += 1;
+                    if p == player {
+                        summary.position = finished;
+                    }
+                }
+                _ if event.player() != player => {}
+                GameEvent::Draw { count, .. } => summary.cards_drawn += count,
+                GameEvent::Play { .. } => summary.cards_played += 1,
+                GameEvent::MissHyo { .. } => summary.missed_hyo_calls += 1,
+                GameEvent::Deal { .. } | GameEvent::CallHyo { .. } => {}
+            }
+        }
+        summary
+    }
+
+    pub fn is_win(&self) -> bool {
+        self.position == 1
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Stats {
+    pub games_played: u32,
+    pub games_won: u32,
+    /// Sum of all finishing positions, used for the average.
+    position_total: u64,
+    pub cards_drawn: u32,
+    pub missed_hyo_calls: u32,
+    current_streak: u32,
+    pub longest_streak: u32,
+    /// How often each card was played.
+    card_plays: Vec<(CardInfo, u32)>,
+    /// Most recent match first.
+    pub history: VecDeque<MatchSummary>,
+}
+
+impl Stats {
+    pub fn load(storage: &Storage) -> Self {
+        storage.load(&STATS_KEY).unwrap_or_default()
+    }
+
+    pub fn store(&self, storage: &Storage) -> Result<(), StorageError> {
+        storage.set(&STATS_KEY, self)
+    }
+
+    /// Add a finished match to the statistics.
+    pub fn record(
+        &mut self,
+        player: usize,
+        players: usize,
+        events: &[GameEvent],
+        finished_at: f64,
+    ) {
+        let summary = MatchSummary::new(player, players, events, finished_at);
+
+        self.games_played += 1;
+        self.position_total += summary.position as u64;
+        self.cards_drawn += summary.cards_drawn;
+        self.missed_hyo_calls += summary.missed_hyo_calls;
+        if summary.is_win() {
+            self.games_won += 1;
+            self.current_streak += 1;
+            self.longest_streak = self.longest_streak.max(self.current_streak);
+        } else {
+            self.current_streak = 0;
+        }
+
+        for event in events {
+            match event {
+                GameEvent::Play { player: p, card } if *p == player => {
+                    match self.card_plays.iter_mut().find(|(c, _)| c == card) {
+                        Some((_, count)) => *count += 1,
+                        None => self.card_plays.push((card.clone(), 1)),
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        self.history.push_front(summary);
+        self.history.truncate(HISTORY_LENGTH);
+    }
+
+    pub fn average_position(&self) -> Option<f64> {
+        if self.games_played == 0 {
+            None
+        } else {
+            Some(self.position_total as f64 / f64::from(self.games_played))
+        }
+    }
+
+    /// The card played most often, the one played first wins ties.
+    pub fn most_played_card(&self) -> Option<&CardInfo> {
+        let mut most: Option<&(CardInfo, u32)> = None;
+        for entry in &self.card_plays {
+            if most.is_none_or(|(_, count)| entry.1 > *count) {
+                most = Some(entry);
+            }
+        }
+        most.map(|(card, _)| card)
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Parse exported statistics.
+    /// Matches with an impossible position are rejected and the history is cut down to
+    /// `HISTORY_LENGTH` matches.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        let mut stats: Self = serde_json::from_str(json)?;
+        if let Some(summary) = stats
+            .history
+            .iter()
+            .find(|summary| summary.position == 0 || summary.position > summary.players)
+        {
+            return Err(serde::de::Error::custom(format!(
+                "position {} of {} players is out of range",
+                summary.position, summary.players
+            )));
+        }
+        stats.history.truncate(HISTORY_LENGTH);
+        Ok(stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(color: &str, number: u8) -> CardInfo {
+        CardInfo {
+            color: String::from(color),
+            number,
+        }
+    }
+
+    /// Player 0 draws two cards and plays three, player 1 finishes first.
+    fn lost_match() -> Vec<GameEvent> {
+        vec![
+            GameEvent::Play {
+                player: 0,
+                card: card("red", 3),
+            },
+            GameEvent::Draw {
+                player: 0,
+                count: 2,
+            },
+            GameEvent::Play {
+                player: 1,
+                card: card("red", 5),
+            },
+            GameEvent::Finish { player: 1 },
+            GameEvent::Play {
+                player: 0,
+                card: card("blue", 7),
+            },
+            GameEvent::MissHyo { player: 0 },
+            GameEvent::Play {
+                player: 0,
+                card: card("red", 3),
+            },
+            GameEvent::Finish { player: 0 },
+        ]
+    }
+
+    fn won_match() -> Vec<GameEvent> {
+        vec![
+            GameEvent::Play {
+                player: 0,
+                card: card("blue", 7),
+            },
+            GameEvent::Finish { player: 0 },
+        ]
+    }
+
+    #[test]
+    fn summarizes_match() {
+        assert_eq!(
+            MatchSummary::new(0, 3, &lost_match(), 1.0),
+            MatchSummary {
+                finished_at: 1.0,
+                players: 3,
+                position: 2,
+                cards_drawn: 2,
+                cards_played: 3,
+                missed_hyo_calls: 1,
+            }
+        );
+        assert_eq!(MatchSummary::new(2, 3, &lost_match(), 1.0).position, 3);
+    }
+
+    #[test]
+    fn records_lifetime_stats() {
+        let mut stats = Stats::default();
+        assert_eq!(stats.average_position(), None);
+        assert_eq!(stats.most_played_card(), None);
+
+        stats.record(0, 2, &won_match(), 1.0);
+        stats.record(0, 2, &won_match(), 2.0);
+        stats.record(0, 2, &lost_match(), 3.0);
+        stats.record(0, 2, &won_match(), 4.0);
+
+        assert_eq!(stats.games_played, 4);
+        assert_eq!(stats.games_won, 3);
+        assert_eq!(stats.average_position(), Some(1.25));
+        assert_eq!(stats.cards_drawn, 2);
+        assert_eq!(stats.missed_hyo_calls, 1);
+        assert_eq!(stats.longest_streak, 2);
+        assert_eq!(stats.most_played_card(), Some(&card("blue", 7)));
+        let finished: Vec<_> = stats.history.iter().map(|m| m.finished_at).collect();
+        assert_eq!(finished, [4.0, 3.0, 2.0, 1.0]);
+    }
+
+    #[test]
+    fn keeps_recent_history() {
+        let mut stats = Stats::default();
+        for i in 0..HISTORY_LENGTH + 5 {
+            stats.record(0, 1, &won_match(), i as f64);
+        }
+        assert_eq!(stats.games_played as usize, HISTORY_LENGTH + 5);
+        assert_eq!(stats.history.len(), HISTORY_LENGTH);
+        assert_eq!(
+            stats.history.front().unwrap().finished_at,
+            (HISTORY_LENGTH + 4) as f64
+        );
+    }
+
+    #[test]
+    fn exports_and_imports() {
+        let mut stats = Stats::default();
+        stats.record(0, 2, &lost_match(), 1.0);
+
+        let json = stats.to_json().unwrap();
+        assert_eq!(Stats::from_json(&json).unwrap(), stats);
+        assert!(Stats::from_json("{}").is_err());
+
+        let mut long = stats.clone();
+        long.history = std::iter::repeat_n(stats.history[0].clone(), HISTORY_LENGTH + 1).collect();
+        let imported = Stats::from_json(&long.to_json().unwrap()).unwrap();
+        assert_eq!(imported.history.len(), HISTORY_LENGTH);
+
+        for position in &[0, 3] {
+            let mut invalid = stats.clone();
+            invalid.history[0].position = *position;
+            assert!(Stats::from_json(&invalid.to_json().unwrap()).is_err());
+        }
+
+        let storage = Storage::memory();
+        assert_eq!(Stats::load(&storage), Stats::default());
+        stats.store(&storage).unwrap();
+        assert_eq!(Stats::load(&storage), stats);
+    }
+}
@@ -0,0 +1,843 @@
+// Parts of this file are derived from the QR Code generator library by Project Nayuki,
+// which is distributed under the following license:
+//
+// Copyright (c) Project Nayuki. (MIT License)
+// https://www.nayuki.io/page/qr-code-generator-library
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy of
+// this software and associated documentation files (the "Software"), to deal in
+// the Software without restriction, including without limitation the rights to
+// use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software is furnished to do so,
+// subject to the following conditions:
+// - The above copyright notice and this permission notice shall be included in
+//   all copies or substantial portions of the Software.
+// - The Software is provided "as is", without warranty of any kind, express or
+//   implied, including but not limited to the warranties of merchantability,
+//   fitness for a particular purpose and noninfringement. In no event shall the
+//   authors or copyright holders be liable for any claim, damages or other
+//   liability, whether in an action of contract, tort or otherwise, arising from,
+//   out of or in connection with the Software or the use or other dealings in the
+//   Software.
+
+//! Minimal QR code encoder.
+//!
+//! Only byte mode is supported, which is all we need to encode links.
+//! The implementation follows ISO/IEC 18004 and closely mirrors Project Nayuki's
+//! QR Code generator, see the license notice at the top of this file.
+
+use thiserror::Error;
+
+const MIN_VERSION: u8 = 1;
+const MAX_VERSION: u8 = 40;
+
+const PENALTY_N1: i32 = 3;
+const PENALTY_N2: i32 = 3;
+const PENALTY_N3: i32 = 40;
+const PENALTY_N4: i32 = 10;
+
+#[rustfmt::skip]
+const ECC_CODEWORDS_PER_BLOCK: [[i8; 41]; 4] = [
+    // Version: (index 0 is padding)
+    // 0,  1,  2,  3,  4,  5,  6,  7,  8,  9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40
+    [-1,  7, 10, 15, 20, 26, 18, 20, 24, 30, 18, 20, 24, 26, 30, 22, 24, 28, 30, 28, 28, 28, 28, 30, 30, 26, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30], // Low
+    [-1, 10, 16, 26, 18, 24, 16, 18, 22, 22, 26, 30, 22, 22, 24, 24, 28, 28, 26, 26, 26, 26, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28], // Medium
+    [-1, 13, 22, 18, 26, 18, 24, 18, 22, 20, 24, 28, 26, 24, 20, 30, 24, 28, 28, 26, 30, 28, 30, 30, 30, 30, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30], // Quartile
+    [-1, 17, 28, 22, 16, 22, 28, 26, 26, 24, 28, 24, 28, 22, 24, 24, 30, 28, 28, 26, 28, 30, 24, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30], // High
+];
+
+#[rustfmt::skip]
+const NUM_ERROR_CORRECTION_BLOCKS: [[i8; 41]; 4] = [
+    // Version: (index 0 is padding)
+    // 0, 1, 2, 3, 4, 5, 6, 7, 8, 9,10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40
+    [-1, 1, 1, 1, 1, 1, 2, 2, 2, 2, 4,  4,  4,  4,  4,  6,  6,  6,  6,  7,  8,  8,  9,  9, 10, 12, 12, 12, 13, 14, 15, 16, 17, 18, 19, 19, 20, 21, 22, 24, 25], // Low
+    [-1, 1, 1, 1, 2, 2, 4, 4, 4, 5, 5,  5,  8,  9,  9, 10, 10, 11, 13, 14, 16, 17, 17, 18, 20, 21, 23, 25, 26, 28, 29, 31, 33, 35, 37, 38, 40, 43, 45, 47, 49], // Medium
+    [-1, 1, 1, 2, 2, 4, 4, 6, 6, 8, 8,  8, 10, 12, 16, 12, 17, 16, 18, 21, 20, 23, 23, 25, 27, 29, 34, 34, 35, 38, 40, 43, 45, 48, 51, 53, 56, 59, 62, 65, 68], // Quartile
+    [-1, 1, 1, 2, 4, 4, 4, 5, 6, 8, 8, 11, 11, 16, 16, 18, 16, 19, 21, 25, 25, 25, 34, 30, 32, 35, 37, 40, 42, 45, 48, 51, 54, 57, 60, 63, 66, 70, 74, 77, 81], // High
+];
+
+/// Amount of redundancy added to a QR code.
+/// Higher levels survive more damage at the cost of a bigger code.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ErrorCorrection {
+    /// Recovers about 7% of the codewords.
+    Low,
+    /// Recovers about 15% of the codewords.
+    #[default]
+    Medium,
+    /// Recovers about 25% of the codewords.
+    Quartile,
+    /// Recovers about 30% of the codewords.
+    High,
+}
+
+impl ErrorCorrection {
+    /// From the lowest to the highest level.
+    const ALL: [Self; 4] = [Self::Low, Self::Medium, Self::Quartile, Self::High];
+
+    fn ordinal(self) -> usize {
+        match self {
+            Self::Low => 0,
+            Self::Medium => 1,
+            Self::Quartile => 2,
+            Self::High => 3,
+        }
+    }
+
+    fn format_bits(self) -> u32 {
+        match self {
+            Self::Low => 1,
+            Self::Medium => 0,
+            Self::Quartile => 3,
+            Self::High => 2,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum QrError {
+    #[error("data too long: {0} bytes don't fit into a QR code")]
+    DataTooLong(usize),
+}
+
+fn get_bit(x: u32, i: u32) -> bool {
+    (x >> i) & 1 != 0
+}
+
+fn num_raw_data_modules(version: u8) -> usize {
+    let ver = usize::from(version);
+    let mut result = (16 * ver + 128) * ver + 64;
+    if ver >= 2 {
+        let num_align = ver / 7 + 2;
+        result -= (25 * num_align - 10) * num_align - 55;
+        if ver >= 7 {
+            result -= 36;
+        }
+    }
+    result
+}
+
+fn table_get(table: &[[i8; 41]; 4], version: u8, ecl: ErrorCorrection) -> usize {
+    table[ecl.ordinal()][usize::from(version)] as usize
+}
+
+fn num_data_codewords(version: u8, ecl: ErrorCorrection) -> usize {
+    num_raw_data_modules(version) / 8
+        - table_get(&ECC_CODEWORDS_PER_BLOCK, version, ecl)
+            * table_get(&NUM_ERROR_CORRECTION_BLOCKS, version, ecl)
+}
+
+fn char_count_bits(version: u8) -> usize {
+    if version < 10 {
+        8
+    } else {
+        16
+    }
+}
+
+/// Multiply two elements of GF(2^8) modulo the polynomial `x^8 + x^4 + x^3 + x^2 + 1`.
+fn reed_solomon_multiply(x: u8, y: u8) -> u8 {
+    let mut z: u8 = 0;
+    for i in (0..8).rev() {
+        z = (z << 1) ^ ((z >> 7) * 0x1D);
+        z ^= ((y >> i) & 1) * x;
+    }
+    z
+}
+
+fn reed_solomon_divisor(degree: usize) -> Vec<u8> {
+    let mut result = vec![0; degree - 1];
+    result.push(1);
+
+    let mut root: u8 = 1;
+    for _ in 0..degree {
+        for j in 0..degree {
+            result[j] = reed_solomon_multiply(result[j], root);
+            if j + 1 < result.len() {
+                result[j] ^= result[j + 1];
+            }
+        }
+        root = reed_solomon_multiply(root, 0x02);
+    }
+    result
+}
+
+fn reed_solomon_remainder(data: &[u8], divisor: &[u8]) -> Vec<u8> {
+    let mut result = vec![0; divisor.len()];
+    for &b in data {
+        let factor = b ^ result.remove(0);
+        result.push(0);
+        for (x, &y) in result.iter_mut().zip(divisor) {
+            *x ^= reed_solomon_multiply(y, factor);
+        }
+    }
+    result
+}
+
+#[derive(Default)]
+struct BitBuffer(Vec<bool>);
+
+impl BitBuffer {
+    fn append_bits(&mut self, val: u32, len: usize) {
+        self.0
+            .extend((0..len).rev().map(|i| get_bit(val, i as u32)));
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn into_bytes(self) -> Vec<u8> {
+        self.0
+            .chunks(8)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .enumerate()
+                    .fold(0, |acc, (i, &bit)| acc | (u8::from(bit) << (7 - i)))
+            })
+            .collect()
+    }
+}
+
+/// Finder-like pattern detection for the mask penalty score.
+struct FinderPenalty {
+    size: i32,
+    run_history: [i32; 7],
+}
+
+impl FinderPenalty {
+    fn new(size: i32) -> Self {
+        Self {
+            size,
+            run_history: [0; 7],
+        }
+    }
+
+    fn add_history(&mut self, mut run_length: i32) {
+        if self.run_history[0] == 0 {
+            // add light border to the initial run
+            run_length += self.size;
+        }
+        self.run_history.copy_within(0..6, 1);
+        self.run_history[0] = run_length;
+    }
+
+    fn count_patterns(&self) -> i32 {
+        let rh = &self.run_history;
+        let n = rh[1];
+        let core = n > 0 && rh[2] == n && rh[3] == n * 3 && rh[4] == n && rh[5] == n;
+        i32::from(core && rh[0] >= n * 4 && rh[6] >= n)
+            + i32::from(core && rh[6] >= n * 4 && rh[0] >= n)
+    }
+
+    fn terminate_and_count(mut self, run_color: bool, mut run_length: i32) -> i32 {
+        if run_color {
+            self.add_history(run_length);
+            run_length = 0;
+        }
+        // add light border to the final run
+        run_length += self.size;
+        self.add_history(run_length);
+        self.count_patterns()
+    }
+}
+
+/// An immutable square grid of dark and light modules.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct QrCode {
+    version: u8,
+    size: usize,
+    ecl: ErrorCorrection,
+    mask: u8,
+    modules: Vec<bool>,
+    is_function: Vec<bool>,
+}
+
+impl QrCode {
+    /// Encode the given bytes using the smallest possible version.
+    /// With `boost_ecl` the error correction level is raised above `ecl` as long as the
+    /// data still fits into that version.
+    /// The mask pattern with the lowest penalty score is chosen automatically.
+    pub fn encode(data: &[u8], mut ecl: ErrorCorrection, boost_ecl: bool) -> Result<Self, QrError> {
+        let fits = |version: u8, ecl: ErrorCorrection| {
+            let capacity_bits = num_data_codewords(version, ecl) * 8;
+            let data_bits = 4 + char_count_bits(version) + data.len() * 8;
+            data.len() < (1 << char_count_bits(version)) && data_bits <= capacity_bits
+        };
+        let version = (MIN_VERSION..=MAX_VERSION)
+            .find(|&version| fits(version, ecl))
+            .ok_or(QrError::DataTooLong(data.len()))?;
+        if boost_ecl {
+            for &higher in &ErrorCorrection::ALL[ecl.ordinal() + 1..] {
+                if fits(version, higher) {
+                    ecl = higher;
+                }
+            }
+        }
+
+        let mut bb = BitBuffer::default();
+        // byte mode indicator
+        bb.append_bits(0b0100, 4);
+        bb.append_bits(data.len() as u32, char_count_bits(version));
+        for &b in data {
+            bb.append_bits(u32::from(b), 8);
+        }
+
+        let capacity_bits = num_data_codewords(version, ecl) * 8;
+        // terminator and padding to the next byte
+        bb.append_bits(0, (capacity_bits - bb.len()).min(4));
+        bb.append_bits(0, (8 - bb.len() % 8) % 8);
+
+        let mut codewords = bb.into_bytes();
+        for &pad in [0xEC, 0x11].iter().cycle() {
+            if codewords.len() * 8 >= capacity_bits {
+                break;
+            }
+            codewords.push(pad);
+        }
+
+        Ok(Self::from_codewords(version, ecl, &codewords))
+    }
+
+    pub fn encode_text(text: &str, ecl: ErrorCorrection, boost_ecl: bool) -> Result<Self, QrError> {
+        Self::encode(text.as_bytes(), ecl, boost_ecl)
+    }
+
+    fn from_codewords(version: u8, ecl: ErrorCorrection, data: &[u8]) -> Self {
+        let size = usize::from(version) * 4 + 17;
+        let mut qr = Self {
+            version,
+            size,
+            ecl,
+            mask: 0,
+            modules: vec![false; size * size],
+            is_function: vec![false; size * size],
+        };
+
+        qr.draw_function_patterns();
+        let all_codewords = qr.add_ecc_and_interleave(data);
+        qr.draw_codewords(&all_codewords);
+
+        let mut min_penalty = i32::MAX;
+        let mut mask = 0;
+        for candidate in 0..8 {
+            qr.apply_mask(candidate);
+            qr.draw_format_bits(candidate);
+            let penalty = qr.penalty_score();
+            if penalty < min_penalty {
+                mask = candidate;
+                min_penalty = penalty;
+            }
+            // XOR is its own inverse
+            qr.apply_mask(candidate);
+        }
+
+        qr.mask = mask;
+        qr.apply_mask(mask);
+        qr.draw_format_bits(mask);
+        qr.is_function.clear();
+        qr
+    }
+
+    #[allow(dead_code)]
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    #[allow(dead_code)]
+    pub fn mask(&self) -> u8 {
+        self.mask
+    }
+
+    /// Width and height of the code in modules.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Whether the module at the given coordinates is dark.
+    /// Coordinates outside of the code are light.
+    #[allow(dead_code)]
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        x < self.size && y < self.size && self.modules[y * self.size + x]
+    }
+
+    /// Iterate over the coordinates of all dark modules.
+    pub fn dark_modules(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let size = self.size;
+        self.modules
+            .iter()
+            .enumerate()
+            .filter(|(_, &dark)| dark)
+            .map(move |(i, _)| (i % size, i / size))
+    }
+
+    fn module(&self, x: i32, y: i32) -> bool {
+        self.modules[y as usize * self.size + x as usize]
+    }
+
+    fn set_function_module(&mut self, x: i32, y: i32, dark: bool) {
+        let i = y as usize * self.size + x as usize;
+        self.modules[i] = dark;
+        self.is_function[i] = true;
+    }
+
+    fn draw_function_patterns(&mut self) {
+        let size = self.size as i32;
+
+        // timing patterns
+        for i in 0..size {
+            self.set_function_module(6, i, i % 2 == 0);
+            self.set_function_module(i, 6, i % 2 == 0);
+        }
+
+        self.draw_finder_pattern(3, 3);
+        self.draw_finder_pattern(size - 4, 3);
+        self.draw_finder_pattern(3, size - 4);
+
+        let positions = self.alignment_pattern_positions();
+        let num_align = positions.len();
+        for (i, &x) in positions.iter().enumerate() {
+            for (j, &y) in positions.iter().enumerate() {
+                // skip the corners occupied by the finder patterns
+                let is_finder_corner =
+                    (i == 0 && (j == 0 || j == num_align - 1)) || (i == num_align - 1 && j == 0);
+                if !is_finder_corner {
+                    self.draw_alignment_pattern(x, y);
+                }
+            }
+        }
+
+        // dummy format bits to reserve the area, overwritten after masking
+        self.draw_format_bits(0);
+        self.draw_version();
+    }
+
+    fn draw_finder_pattern(&mut self, x: i32, y: i32) {
+        let size = self.size as i32;
+        for dy in -4..=4 {
+            for dx in -4..=4 {
+                let (xx, yy) = (x + dx, y + dy);
+                if (0..size).contains(&xx) && (0..size).contains(&yy) {
+                    let dist = dx.abs().max(dy.abs());
+                    self.set_function_module(xx, yy, dist != 2 && dist != 4);
+                }
+            }
+        }
+    }
+
+    fn draw_alignment_pattern(&mut self, x: i32, y: i32) {
+        for dy in -2..=2 {
+            for dx in -2..=2 {
+                self.set_function_module(x + dx, y + dy, dx.abs().max(dy.abs()) != 1);
+            }
+        }
+    }
+
+    fn alignment_pattern_positions(&self) -> Vec<i32> {
+        let ver = i32::from(self.version);
+        if ver == 1 {
+            return vec![];
+        }
+
+        let num_align = ver / 7 + 2;
+        let step = (ver * 8 + num_align * 3 + 5) / (num_align * 4 - 4) * 2;
+        let mut result: Vec<i32> = (0..num_align - 1)
+            .map(|i| self.size as i32 - 7 - i * step)
+            .collect();
+        result.push(6);
+        result.reverse();
+        result
+    }
+
+    fn draw_format_bits(&mut self, mask: u8) {
+        let data = self.ecl.format_bits() << 3 | u32::from(mask);
+        let mut rem = data;
+        for _ in 0..10 {
+            rem = (rem << 1) ^ ((rem >> 9) * 0x537);
+        }
+        let bits = (data << 10 | rem) ^ 0x5412;
+
+        // first copy
+        for i in 0..6 {
+            self.set_function_module(8, i, get_bit(bits, i as u32));
+        }
+        self.set_function_module(8, 7, get_bit(bits, 6));
+        self.set_function_module(8, 8, get_bit(bits, 7));
+        self.set_function_module(7, 8, get_bit(bits, 8));
+        for i in 9..15 {
+            self.set_function_module(14 - i, 8, get_bit(bits, i as u32));
+        }
+
+        // second copy
+        let size = self.size as i32;
+        for i in 0..8 {
+            self.set_function_module(size - 1 - i, 8, get_bit(bits, i as u32));
+        }
+        for i in 8..15 {
+            self.set_function_module(8, size - 15 + i, get_bit(bits, i as u32));
+        }
+        // always dark
+        self.set_function_module(8, size - 8, true);
+    }
+
+    fn draw_version(&mut self) {
+        if self.version < 7 {
+            return;
+        }
+
+        let ver = u32::from(self.version);
+        let mut rem = ver;
+        for _ in 0..12 {
+            rem = (rem << 1) ^ ((rem >> 11) * 0x1F25);
+        }
+        let bits = ver << 12 | rem;
+
+        let size = self.size as i32;
+        for i in 0..18 {
+            let dark = get_bit(bits, i as u32);
+            let a = size - 11 + i % 3;
+            let b = i / 3;
+            self.set_function_module(a, b, dark);
+            self.set_function_module(b, a, dark);
+        }
+    }
+
+    fn add_ecc_and_interleave(&self, data: &[u8]) -> Vec<u8> {
+        let num_blocks = table_get(&NUM_ERROR_CORRECTION_BLOCKS, self.version, self.ecl);
+        let block_ecc_len = table_get(&ECC_CODEWORDS_PER_BLOCK, self.version, self.ecl);
+        let raw_codewords = num_raw_data_modules(self.version) / 8;
+        let num_short_blocks = num_blocks - raw_codewords % num_blocks;
+        let short_block_len = raw_codewords / num_blocks;
+
+        let divisor = reed_solomon_divisor(block_ecc_len);
+        let mut blocks = Vec::with_capacity(num_blocks);
+        let mut k = 0;
+        for i in 0..num_blocks {
+            let data_len = short_block_len - block_ecc_len + usize::from(i >= num_short_blocks);
+            let mut block = data[k..k + data_len].to_vec();
+            k += data_len;
+            let ecc = reed_solomon_remainder(&block, &divisor);
+            if i < num_short_blocks {
+                block.push(0);
+            }
+            block.extend(ecc);
+            blocks.push(block);
+        }
+
+        let mut result = Vec::with_capacity(raw_codewords);
+        for i in 0..=short_block_len {
+            for (j, block) in blocks.iter().enumerate() {
+                // skip the padding byte of short blocks
+                if i != short_block_len - block_ecc_len || j >= num_short_blocks {
+                    result.push(block[i]);
+                }
+            }
+        }
+        result
+    }
+
+    fn draw_codewords(&mut self, data: &[u8]) {
+        let size = self.size as i32;
+        let mut i = 0;
+        let mut right = size - 1;
+        while right >= 1 {
+            if right == 6 {
+                right = 5;
+            }
+            for vert in 0..size {
+                for j in 0..2 {
+                    let x = right - j;
+                    let upward = (right + 1) & 2 == 0;
+                    let y = if upward { size - 1 - vert } else { vert };
+                    let idx = y as usize * self.size + x as usize;
+                    if !self.is_function[idx] && i < data.len() * 8 {
+                        self.modules[idx] = get_bit(u32::from(data[i >> 3]), 7 - (i as u32 & 7));
+                        i += 1;
+                    }
+                }
+            }
+            right -= 2;
+        }
+    }
+
+    fn apply_mask(&mut self, mask: u8) {
+        for y in 0..self.size {
+            for x in 0..self.size {
+                let invert = match mask {
+                    0 => (x + y) % 2 == 0,
+                    1 => y % 2 == 0,
+                    2 => x % 3 == 0,
+                    3 => (x + y) % 3 == 0,
+                    4 => (x / 3 + y / 2) % 2 == 0,
+                    5 => x * y % 2 + x * y % 3 == 0,
+                    6 => (x * y % 2 + x * y % 3) % 2 == 0,
+                    7 => ((x + y) % 2 + x * y % 3) % 2 == 0,
+                    _ => unreachable!("invalid mask {}", mask),
+                };
+                let i = y * self.size + x;
+                self.modules[i] ^= invert && !self.is_function[i];
+            }
+        }
+    }
+
+    fn penalty_score(&self) -> i32 {
+        let size = self.size as i32;
+        let mut result = 0;
+
+        // adjacent modules of the same color and finder-like patterns in rows and columns
+        for transpose in [false, true].iter().copied() {
+            for a in 0..size {
+                let mut run_color = false;
+                let mut run_length = 0;
+                let mut history = FinderPenalty::new(size);
+                for b in 0..size {
+                    let color = if transpose {
+                        self.module(a, b)
+                    } else {
+                        self.module(b, a)
+                    };
+                    if color == run_color {
+                        run_length += 1;
+                        if run_length == 5 {
+                            result += PENALTY_N1;
+                        } else if run_length > 5 {
+                            result += 1;
+                        }
+                    } else {
+                        history.add_history(run_length);
+                        if !run_color {
+                            result += history.count_patterns() * PENALTY_N3;
+                        }
+                        run_color = color;
+                        run_length = 1;
+                    }
+                }
+                result += history.terminate_and_count(run_color, run_length) * PENALTY_N3;
+            }
+        }
+
+        // 2x2 blocks of the same color
+        for y in 0..size - 1 {
+            for x in 0..size - 1 {
+                let color = self.module(x, y);
+                if color == self.module(x + 1, y)
+                    && color == self.module(x, y + 1)
+                    && color == self.module(x + 1, y + 1)
+                {
+                    result += PENALTY_N2;
+                }
+            }
+        }
+
+        // balance of dark and light modules
+        let dark = self.modules.iter().filter(|&&dark| dark).count() as i32;
+        let total = size * size;
+        let k = ((dark * 20 - total * 10).abs() + total - 1) / total - 1;
+        result += k * PENALTY_N4;
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(qr: &QrCode) -> Vec<String> {
+        (0..qr.size())
+            .map(|y| {
+                (0..qr.size())
+                    .map(|x| if qr.get(x, y) { '#' } else { '.' })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn assert_matches(qr: &QrCode, expected: &[&str]) {
+        assert_eq!(qr.size(), expected.len());
+        for (y, (actual, expected)) in render(qr).iter().zip(expected).enumerate() {
+            assert_eq!(actual, expected, "row {} differs", y);
+        }
+    }
+
+    #[rustfmt::skip]
+    const HYO_LOW: [&str; 21] = [
+        "#######..#.##.#######",
+        "#.....#..###..#.....#",
+        "#.###.#.##.##.#.###.#",
+        "#.###.#..#.#..#.###.#",
+        "#.###.#...#.#.#.###.#",
+        "#.....#.....#.#.....#",
+        "#######.#.#.#.#######",
+        "........##.##........",
+        "###.########.##...#..",
+        "#.##.#....#...#..##.#",
+        "#####.#.....#...#####",
+        "##.##..##.#...#....#.",
+        "##.#.######.#.#.#....",
+        "........##.#.#.#..##.",
+        "#######.#..#.###.#.##",
+        "#.....#.######.###..#",
+        "#.###.#.##.#.###..#.#",
+        "#.###.#...#...#...##.",
+        "#.###.#.#...#...#...#",
+        "#.....#.#.....#...##.",
+        "#######.##..#.#.#.###",
+    ];
+
+    #[rustfmt::skip]
+    const HELLO_WORLD_QUARTILE: [&str; 21] = [
+        "#######.#..#..#######",
+        "#.....#..#....#.....#",
+        "#.###.#.#..#..#.###.#",
+        "#.###.#.#.##..#.###.#",
+        "#.###.#..##.#.#.###.#",
+        "#.....#.##.#..#.....#",
+        "#######.#.#.#.#######",
+        "........#.###........",
+        ".#.#.####..#####.##.#",
+        "..####...#....##...#.",
+        ".#..#.##.#.##..#.##.#",
+        "#.###..#.####.#.##.##",
+        ".#.##.#.#.##.####.#..",
+        "........##..#...#.#..",
+        "#######.##.#..######.",
+        "#.....#.#####..#....#",
+        "#.###.#..#..###...##.",
+        "#.###.#.#.#....######",
+        "#.###.#...#.#.#.#.#.#",
+        "#.....#.#.##.#.......",
+        "#######...#.#..#.###.",
+    ];
+
+    #[rustfmt::skip]
+    const HELLO_HIGH: [&str; 21] = [
+        "#######.....#.#######",
+        "#.....#.......#.....#",
+        "#.###.#.##.#..#.###.#",
+        "#.###.#.#.#...#.###.#",
+        "#.###.#...###.#.###.#",
+        "#.....#...#...#.....#",
+        "#######.#.#.#.#######",
+        ".........###.........",
+        "...##.##.###.....##..",
+        "#.#..#.#.##...#####..",
+        "##.#.##.##.#.#.#..###",
+        "##..##.#.#..##.##.#..",
+        ".#..#####.....####.#.",
+        "........#.#.###..#.#.",
+        "#######.#.#...#...#..",
+        "#.....#..###.#...####",
+        "#.###.#.#.#.#...##.##",
+        "#.###.#.#.####..#....",
+        "#.###.#...##.########",
+        "#.....#..############",
+        "#######...#..#.......",
+    ];
+
+    #[rustfmt::skip]
+    const INVITE_LINK_MEDIUM: [&str; 29] = [
+        "#######..#.###..#####.#######",
+        "#.....#.#.#...###.###.#.....#",
+        "#.###.#...#.###.#..##.#.###.#",
+        "#.###.#..#..####..##..#.###.#",
+        "#.###.#.#..##.#..####.#.###.#",
+        "#.....#..#..##.....##.#.....#",
+        "#######.#.#.#.#.#.#.#.#######",
+        ".........###.##.##..#........",
+        "#.#.#.#...###.##.#.##...#..#.",
+        "#.###..#..##..##......##.#..#",
+        "..###.###....#...#....###.###",
+        ".#.#.#.#.#.#...#.##.###....#.",
+        ".#.####.#...#...##.##.##.#.##",
+        ".##..#..#.#..#.##...###..#..#",
+        "####.####.#...###.#..#.#.#.##",
+        "###......###.##.####.#####.#.",
+        "..#...#.##.#..####.#.##..#.##",
+        ".#.#.#..#.#.#.##....###..##.#",
+        "#..#.##.##.###..##..#..##..##",
+        ".#.....###.....###.#...###.#.",
+        "#...####...##...#########....",
+        "........######.##..##...#.###",
+        "#######..##.#.#..#.##.#.##.##",
+        "#.....#....####..##.#...##.#.",
+        "#.###.#.#.###.#.#...#####...#",
+        "#.###.#..#....####..#..##.###",
+        "#.###.#.####....#.##...###..#",
+        "#.....#..........####.#.#..#.",
+        "#######.###.###.#...##..##.##",
+    ];
+
+    #[test]
+    fn reed_solomon_hello_world() {
+        // version 1-M codewords for "HELLO WORLD" in alphanumeric mode
+        let data = [
+            32, 91, 11, 120, 209, 114, 220, 77, 67, 64, 236, 17, 236, 17, 236, 17,
+        ];
+        let ecc = reed_solomon_remainder(&data, &reed_solomon_divisor(10));
+        assert_eq!(ecc, [196, 35, 39, 119, 235, 215, 231, 226, 93, 23]);
+    }
+
+    #[test]
+    fn encode_low() {
+        let qr = QrCode::encode_text("Hyo", ErrorCorrection::Low, false).unwrap();
+        assert_eq!((qr.version(), qr.mask()), (1, 0));
+        assert_matches(&qr, &HYO_LOW);
+    }
+
+    #[test]
+    fn encode_quartile() {
+        let qr = QrCode::encode_text("HELLO WORLD", ErrorCorrection::Quartile, false).unwrap();
+        assert_eq!((qr.version(), qr.mask()), (1, 7));
+        assert_matches(&qr, &HELLO_WORLD_QUARTILE);
+    }
+
+    #[test]
+    fn encode_high() {
+        let qr = QrCode::encode_text("hello", ErrorCorrection::High, false).unwrap();
+        assert_eq!((qr.version(), qr.mask()), (1, 6));
+        assert_matches(&qr, &HELLO_HIGH);
+    }
+
+    #[test]
+    fn encode_invite_link() {
+        let qr = QrCode::encode_text(
+            "https://siku2.io/hyo/#/join/ABCD",
+            ErrorCorrection::Medium,
+            false,
+        )
+        .unwrap();
+        assert_eq!((qr.version(), qr.mask()), (3, 0));
+        assert_matches(&qr, &INVITE_LINK_MEDIUM);
+    }
+
+    #[test]
+    fn version_selection() {
+        let version = |len, ecl| QrCode::encode(&vec![0; len], ecl, false).unwrap().version();
+        assert_eq!(version(17, ErrorCorrection::Low), 1);
+        assert_eq!(version(18, ErrorCorrection::Low), 2);
+        assert_eq!(version(106, ErrorCorrection::Medium), 6);
+        assert_eq!(version(107, ErrorCorrection::Medium), 7);
+        assert_eq!(version(2953, ErrorCorrection::Low), 40);
+    }
+
+    #[test]
+    fn boosts_error_correction() {
+        let qr = QrCode::encode_text("hello", ErrorCorrection::Low, true).unwrap();
+        assert_eq!((qr.version(), qr.ecl), (1, ErrorCorrection::High));
+        assert_matches(&qr, &HELLO_HIGH);
+
+        // 17 bytes only fit into version 1 with low error correction
+        let qr = QrCode::encode(&[0; 17], ErrorCorrection::Low, true).unwrap();
+        assert_eq!((qr.version(), qr.ecl), (1, ErrorCorrection::Low));
+    }
+
+    #[test]
+    fn data_too_long() {
+        let res = QrCode::encode(&[0; 2954], ErrorCorrection::Low, true);
+        assert!(matches!(res, Err(QrError::DataTooLong(2954))));
+        let res = QrCode::encode(&[0; 1274], ErrorCorrection::High, false);
+        assert!(matches!(res, Err(QrError::DataTooLong(1274))));
+    }
+}
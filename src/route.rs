@@ -0,0 +1,198 @@
+use wasm_bindgen::{closure::Closure, JsCast};
+use yew::Callback;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Route {
+    Start,
+    Join { code: Option<String> },
+    Settings,
+    Stats,
+    Game,
+}
+
+/// Normalize a user supplied room code.
+/// Room codes are case-insensitive so they're stored in uppercase with everything but
+/// alphanumeric characters stripped.
+pub fn normalize_room_code(code: &str) -> String {
+    code.chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+/// Decode the `%XX` escapes the browser adds to the fragment.
+/// Malformed escapes are kept as they are.
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .filter(|_| bytes[i] == b'%')
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+impl Route {
+    /// Parse a route from the fragment part of the URL.
+    /// Unknown routes resolve to `Route::Start`.
+    pub fn from_hash(hash: &str) -> Self {
+        let path = hash.trim_start_matches('#').trim_matches('/');
+        let mut parts = path.split('/').filter(|part| !part.is_empty());
+
+        match (parts.next(), parts.next()) {
+            (Some("join"), code) => {
+                let code = code
+                    .map(|code| normalize_room_code(&percent_decode(code)))
+                    .filter(|code| !code.is_empty());
+                Self::Join { code }
+            }
+            (Some("settings"), _) => Self::Settings,
+            (Some("stats"), _) => Self::Stats,
+            (Some("game"), _) => Self::Game,
+            _ => Self::Start,
+        }
+    }
+
+    pub fn to_hash(&self) -> String {
+        match self {
+            Self::Start => String::from("#/"),
+            Self::Join { code: Some(code) } => format!("#/join/{}", code),
+            Self::Join { code: None } => String::from("#/join"),
+            Self::Settings => String::from("#/settings"),
+            Self::Stats => String::from("#/stats"),
+            Self::Game => String::from("#/game"),
+        }
+    }
+
+    /// Absolute URL pointing to the route.
+    /// This is what should be shared with other people, for example as an invite link.
+    pub fn to_url(&self) -> Option<String> {
+        let location = web_sys::window()?.location();
+        let base = format!("{}{}", location.origin().ok()?, location.pathname().ok()?);
+        Some(format!("{}{}", base, self.to_hash()))
+    }
+
+    pub fn current() -> Self {
+        let hash = web_sys::window().and_then(|window| window.location().hash().ok());
+        hash.map_or(Self::Start, |hash| Self::from_hash(&hash))
+    }
+
+    pub fn navigate(&self) {
+        if let Some(window) = web_sys::window() {
+            if let Err(e) = window.location().set_hash(&self.to_hash()) {
+                log::error!("failed to navigate to {:?}: {:?}", self, e);
+            }
+        }
+    }
+}
+
+/// Listens for changes to the route for as long as it's alive.
+pub struct RouteListener {
+    closure: Closure<dyn FnMut()>,
+}
+
+impl RouteListener {
+    pub fn new(callback: Callback<Route>) -> Self {
+        let closure =
+            Closure::wrap(Box::new(move || callback.emit(Route::current())) as Box<dyn FnMut()>);
+        if let Some(window) = web_sys::window() {
+            if let Err(e) = window
+                .add_event_listener_with_callback("hashchange", closure.as_ref().unchecked_ref())
+            {
+                log::error!("failed to listen for route changes: {:?}", e);
+            }
+        }
+
+        Self { closure }
+    }
+}
+
+impl Drop for RouteListener {
+    fn drop(&mut self) {
+        if let Some(window) = web_sys::window() {
+            let _ = window.remove_event_listener_with_callback(
+                "hashchange",
+                self.closure.as_ref().unchecked_ref(),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn join(code: Option<&str>) -> Route {
+        Route::Join {
+            code: code.map(ToOwned::to_owned),
+        }
+    }
+
+    #[test]
+    fn parses_hashes() {
+        let cases = [
+            ("", Route::Start),
+            ("#", Route::Start),
+            ("#/", Route::Start),
+            ("#/settings", Route::Settings),
+            ("#settings/", Route::Settings),
+            ("#/stats", Route::Stats),
+            ("#/game", Route::Game),
+            ("#/join", join(None)),
+            ("#/join/ABCD", join(Some("ABCD"))),
+            // unknown routes
+            ("#/lobby", Route::Start),
+            ("#/Settings", Route::Start),
+            ("#//", Route::Start),
+        ];
+        for (hash, route) in &cases {
+            assert_eq!(&Route::from_hash(hash), route, "parsing {:?}", hash);
+        }
+    }
+
+    #[test]
+    fn normalizes_room_codes() {
+        let cases = [
+            ("#/join/abcd", join(Some("ABCD"))),
+            ("#/join/aB-c d", join(Some("ABCD"))),
+            ("#/join/ab%20cd", join(Some("ABCD"))),
+            ("#/join/ab%2", join(Some("AB2"))),
+            ("#/join/ab12/extra", join(Some("AB12"))),
+            // nothing usable is left of these
+            ("#/join/", join(None)),
+            ("#/join/---", join(None)),
+            ("#/join/äö", join(None)),
+        ];
+        for (hash, route) in &cases {
+            assert_eq!(&Route::from_hash(hash), route, "parsing {:?}", hash);
+        }
+    }
+
+    #[test]
+    fn round_trips_hashes() {
+        for route in &[
+            Route::Start,
+            join(None),
+            join(Some("ABCD")),
+            Route::Settings,
+            Route::Stats,
+            Route::Game,
+        ] {
+            assert_eq!(&Route::from_hash(&route.to_hash()), route);
+        }
+    }
+}
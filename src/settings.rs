@@ -0,0 +1,184 @@
+//! Preferences that change how the game looks and behaves.
+//!
+//! The settings are loaded once by the loading view and passed down to everything that
+//! needs them, like the locale. Changing them re-renders the app right away.
+
+use crate::{
+    locale::{messages, Locale},
+    storage::{Key, Storage, StorageError},
+};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+use web_sys::HtmlElement;
+
+const SETTINGS_KEY: Key<Settings> = Key::new("settings");
+/// Highest sound volume.
+pub const MAX_VOLUME: u8 = 100;
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AnimationSpeed {
+    Slow,
+    #[default]
+    Normal,
+    Fast,
+}
+
+impl AnimationSpeed {
+    pub const ALL: [Self; 3] = [Self::Slow, Self::Normal, Self::Fast];
+
+    /// Used as the value of form controls.
+    pub fn id(self) -> &'static str {
+        match self {
+            Self::Slow => "slow",
+            Self::Normal => "normal",
+            Self::Fast => "fast",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|speed| speed.id() == id)
+    }
+
+    pub fn localize(self, locale: &Locale) -> String {
+        let message = match self {
+            Self::Slow => messages::ANIMATION_SPEED_SLOW,
+            Self::Normal => messages::ANIMATION_SPEED_NORMAL,
+            Self::Fast => messages::ANIMATION_SPEED_FAST,
+        };
+        locale.localize(&message).into_owned()
+    }
+
+    /// Multiplier for the duration of animations.
+    fn duration_factor(self) -> f64 {
+        match self {
+            Self::Slow => 2.0,
+            Self::Normal => 1.0,
+            Self::Fast => 0.5,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct Settings {
+    pub animation_speed: AnimationSpeed,
+    /// Turns off animations entirely.
+    pub reduced_motion: bool,
+    /// Sort the hand by color and number.
+    pub auto_sort_hand: bool,
+    /// Cards have to be clicked twice to be played.
+    pub confirm_before_play: bool,
+    /// Highlight the cards that can be played.
+    pub playable_card_hints: bool,
+    /// From 0 to `MAX_VOLUME`.
+    pub sound_volume: u8,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            animation_speed: AnimationSpeed::default(),
+            reduced_motion: false,
+            auto_sort_hand: false,
+            confirm_before_play: false,
+            playable_card_hints: true,
+            sound_volume: MAX_VOLUME / 2,
+        }
+    }
+}
+
+impl Settings {
+    /// Default settings that follow the preferences of the browser.
+    fn from_browser() -> Self {
+        let reduced_motion = web_sys::window()
+            .and_then(|window| window.match_media("(prefers-reduced-motion: reduce)").ok())
+            .flatten()
+            .is_some_and(|query| query.matches());
+        Self {
+            reduced_motion,
+            ..Self::default()
+        }
+    }
+
+    /// The stored settings or the browser defaults if there aren't any.
+    pub fn load(storage: &Storage) -> Self {
+        storage
+            .load(&SETTINGS_KEY)
+            .unwrap_or_else(Self::from_browser)
+    }
+
+    pub fn store(&self, storage: &Storage) -> Result<(), StorageError> {
+        storage.set(&SETTINGS_KEY, self)
+    }
+
+    /// Multiplier for the duration of animations, 0 turns them off.
+    pub fn animation_scale(&self) -> f64 {
+        if self.reduced_motion {
+            0.0
+        } else {
+            self.animation_speed.duration_factor()
+        }
+    }
+
+    /// Scale the transitions of the stylesheets through `--animation-scale`.
+    pub fn apply_to_document(&self) {
+        let root = match web_sys::window()
+            .and_then(|window| window.document())
+            .and_then(|document| document.document_element())
+            .and_then(|root| root.dyn_into::<HtmlElement>().ok())
+        {
+            Some(root) => root,
+            None => return,
+        };
+
+        let scale = self.animation_scale().to_string();
+        if let Err(e) = root.style().set_property("--animation-scale", &scale) {
+            log::error!("failed to apply settings to document: {:?}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scales_animations() {
+        let mut settings = Settings::default();
+        assert_eq!(settings.animation_scale(), 1.0);
+
+        settings.animation_speed = AnimationSpeed::Fast;
+        assert_eq!(settings.animation_scale(), 0.5);
+        settings.animation_speed = AnimationSpeed::Slow;
+        assert_eq!(settings.animation_scale(), 2.0);
+
+        settings.reduced_motion = true;
+        assert_eq!(settings.animation_scale(), 0.0);
+    }
+
+    #[test]
+    fn stores_settings() {
+        let storage = Storage::memory();
+        let settings = Settings {
+            animation_speed: AnimationSpeed::Fast,
+            auto_sort_hand: true,
+            sound_volume: 0,
+            ..Settings::default()
+        };
+        settings.store(&storage).unwrap();
+        assert_eq!(Settings::load(&storage), settings);
+    }
+
+    #[test]
+    fn fills_in_new_settings() {
+        let settings: Settings = serde_json::from_str(r#"{"animation_speed":"slow"}"#).unwrap();
+        assert_eq!(
+            settings,
+            Settings {
+                animation_speed: AnimationSpeed::Slow,
+                ..Settings::default()
+            }
+        );
+    }
+}
@@ -0,0 +1,101 @@
+use super::StorageError;
+use std::{cell::RefCell, collections::BTreeMap};
+use wasm_bindgen::{JsCast, JsValue};
+
+/// Raw string storage the typed keys are stored in.
+pub trait Backend {
+    fn get(&self, key: &str) -> Result<Option<String>, StorageError>;
+    fn set(&self, key: &str, value: &str) -> Result<(), StorageError>;
+    fn remove(&self, key: &str) -> Result<(), StorageError>;
+}
+
+impl From<JsValue> for StorageError {
+    fn from(v: JsValue) -> Self {
+        let is_quota = v
+            .dyn_ref::<js_sys::Error>()
+            .is_some_and(|err| err.name() == "QuotaExceededError");
+        if is_quota {
+            Self::QuotaExceeded
+        } else {
+            Self::Js(v)
+        }
+    }
+}
+
+/// The browser's local storage.
+pub struct LocalStorage(web_sys::Storage);
+
+impl LocalStorage {
+    /// Fails if local storage is disabled, as it is in some private browsing modes.
+    pub fn new() -> Result<Self, StorageError> {
+        let window = web_sys::window().ok_or(StorageError::Unavailable)?;
+        match window.local_storage()? {
+            Some(storage) => Ok(Self(storage)),
+            None => Err(StorageError::Unavailable),
+        }
+    }
+}
+
+impl Backend for LocalStorage {
+    fn get(&self, key: &str) -> Result<Option<String>, StorageError> {
+        Ok(self.0.get_item(key)?)
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<(), StorageError> {
+        Ok(self.0.set_item(key, value)?)
+    }
+
+    fn remove(&self, key: &str) -> Result<(), StorageError> {
+        Ok(self.0.remove_item(key)?)
+    }
+}
+
+/// Storage that only lasts as long as the page.
+/// Used for tests and when local storage is unavailable.
+#[derive(Debug, Default)]
+pub struct Memory {
+    items: RefCell<BTreeMap<String, String>>,
+    /// Maximum number of bytes the keys and values may take up.
+    quota: Option<usize>,
+}
+
+impl Memory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[cfg(test)]
+    pub fn with_quota(quota: usize) -> Self {
+        Self {
+            items: RefCell::default(),
+            quota: Some(quota),
+        }
+    }
+}
+
+impl Backend for Memory {
+    fn get(&self, key: &str) -> Result<Option<String>, StorageError> {
+        Ok(self.items.borrow().get(key).cloned())
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<(), StorageError> {
+        let mut items = self.items.borrow_mut();
+        if let Some(quota) = self.quota {
+            let used: usize = items
+                .iter()
+                .filter(|(k, _)| k.as_str() != key)
+                .map(|(k, v)| k.len() + v.len())
+                .sum();
+            if used + key.len() + value.len() > quota {
+                return Err(StorageError::QuotaExceeded);
+            }
+        }
+        items.insert(key.to_owned(), value.to_owned());
+        Ok(())
+    }
+
+    fn remove(&self, key: &str) -> Result<(), StorageError> {
+        self.items.borrow_mut().remove(key);
+        Ok(())
+    }
+}
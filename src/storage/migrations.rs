@@ -0,0 +1,45 @@
+//! Upgrades for data written by older versions of the app.
+//! Migrations work on the raw strings since the types they were written with may be gone.
+//! Never change a migration once it's released, add a new one instead.
+
+use super::{Backend, Migration, StorageError};
+
+/// All migrations in order, the schema version is the number of migrations.
+pub static MIGRATIONS: &[Migration] = &[namespace_legacy_keys];
+
+/// Before version 1 values were stored as plain strings without a namespace.
+fn namespace_legacy_keys(backend: &dyn Backend) -> Result<(), StorageError> {
+    let encode = |value: &str| serde_json::to_string(value).map_err(StorageError::Encode);
+
+    if let Some(language) = backend.get("language")? {
+        backend.set("hyo.language", &encode(&language)?)?;
+        backend.remove("language")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{Key, Memory, Storage};
+
+    #[test]
+    fn migrates_legacy_keys() {
+        let backend = Memory::new();
+        backend.set("language", "de-DE").unwrap();
+        backend.set("unrelated", "kept").unwrap();
+
+        let storage = Storage::new(backend);
+        storage.migrate(MIGRATIONS).unwrap();
+
+        assert_eq!(
+            storage.load(&Key::<String>::new("language")).as_deref(),
+            Some("de-DE")
+        );
+        assert_eq!(storage.backend.get("language").unwrap(), None);
+        assert_eq!(
+            storage.backend.get("unrelated").unwrap().as_deref(),
+            Some("kept")
+        );
+    }
+}
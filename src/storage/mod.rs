@@ -0,0 +1,295 @@
+//! Typed and versioned access to the browser's local storage.
+//!
+//! Values are serialized as JSON under keys in the app's namespace, like `hyo.settings`.
+//! The stored data has a schema version. Whenever the layout changes a migration is
+//! appended to [`migrations::MIGRATIONS`] which upgrades data written by older versions.
+//!
+//! Storage problems never stop the app. If local storage is disabled, as it is in some
+//! private browsing modes, data is only kept in memory for the session. Failed writes are
+//! returned to the caller, who decides whether the user needs to know.
+
+mod backend;
+mod migrations;
+
+pub use backend::{Backend, LocalStorage, Memory};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{borrow::Cow, fmt, marker::PhantomData, rc::Rc};
+use thiserror::Error;
+use wasm_bindgen::JsValue;
+
+const NAMESPACE: &str = "hyo";
+const VERSION_KEY: Key<u32> = Key::new("version");
+
+thread_local! {
+    static LOCAL: Storage = Storage::open_local();
+}
+
+#[derive(Debug, Error)]
+pub enum StorageError {
+    #[error("storage quota exceeded")]
+    QuotaExceeded,
+    #[error("storage unavailable")]
+    Unavailable,
+    #[error("failed to encode value: {0}")]
+    Encode(serde_json::Error),
+    #[error("failed to decode value: {0}")]
+    Decode(serde_json::Error),
+    #[error("storage error: {0:?}")]
+    Js(JsValue),
+}
+
+/// Upgrades the raw data from one schema version to the next.
+pub type Migration = fn(&dyn Backend) -> Result<(), StorageError>;
+
+/// Name of a stored value of type `T`.
+pub struct Key<T> {
+    name: Cow<'static, str>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Key<T> {
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name: Cow::Borrowed(name),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Key nested below this one, for example `fetch-cache` with the suffix `a.ftl` becomes
+    /// `fetch-cache.a.ftl`.
+    pub fn with_suffix(&self, suffix: &str) -> Self {
+        Self {
+            name: format!("{}.{}", self.name, suffix).into(),
+            _marker: PhantomData,
+        }
+    }
+
+    fn storage_key(&self) -> String {
+        format!("{}.{}", NAMESPACE, self.name)
+    }
+}
+
+impl<T> Clone for Key<T> {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> fmt::Debug for Key<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Key").field(&self.name).finish()
+    }
+}
+
+#[derive(Clone)]
+pub struct Storage {
+    backend: Rc<dyn Backend>,
+}
+
+impl Storage {
+    /// Storage using the backend as-is, without migrating it.
+    pub fn new(backend: impl Backend + 'static) -> Self {
+        Self {
+            backend: Rc::new(backend),
+        }
+    }
+
+    pub fn memory() -> Self {
+        Self::new(Memory::new())
+    }
+
+    /// The app's storage.
+    /// This is local storage if it's available, migrated to the current schema on first use.
+    pub fn local() -> Self {
+        LOCAL.with(Clone::clone)
+    }
+
+    fn open_local() -> Self {
+        let storage = match LocalStorage::new() {
+            Ok(backend) => Self::new(backend),
+            Err(err) => {
+                log::warn!("{}, nothing will be persisted", err);
+                Self::memory()
+            }
+        };
+        if let Err(err) = storage.migrate(migrations::MIGRATIONS) {
+            log::error!("failed to migrate storage: {}", err);
+        }
+        storage
+    }
+
+    pub fn get<T: DeserializeOwned>(&self, key: &Key<T>) -> Result<Option<T>, StorageError> {
+        match self.backend.get(&key.storage_key())? {
+            Some(raw) => serde_json::from_str(&raw)
+                .map(Some)
+                .map_err(StorageError::Decode),
+            None => Ok(None),
+        }
+    }
+
+    /// Value of the key, `None` if it's missing or can't be read.
+    pub fn load<T: DeserializeOwned>(&self, key: &Key<T>) -> Option<T> {
+        self.get(key).unwrap_or_else(|err| {
+            log::warn!("failed to load {:?}: {}", key, err);
+            None
+        })
+    }
+
+    pub fn set<T: Serialize>(&self, key: &Key<T>, value: &T) -> Result<(), StorageError> {
+        let raw = serde_json::to_string(value).map_err(StorageError::Encode)?;
+        self.backend.set(&key.storage_key(), &raw)
+    }
+
+    pub fn remove<T>(&self, key: &Key<T>) -> Result<(), StorageError> {
+        self.backend.remove(&key.storage_key())
+    }
+
+    /// Copy the raw value of the key to `<key>.backup`, like before replacing a value that
+    /// can't be read anymore.
+    pub fn back_up<T>(&self, key: &Key<T>) -> Result<(), StorageError> {
+        if let Some(raw) = self.backend.get(&key.storage_key())? {
+            let backup = key.with_suffix("backup");
+            self.backend.set(&backup.storage_key(), &raw)?;
+        }
+        Ok(())
+    }
+
+    /// Schema version of the stored data, 0 if nothing was ever migrated.
+    pub fn version(&self) -> Result<u32, StorageError> {
+        Ok(self.get(&VERSION_KEY)?.unwrap_or(0))
+    }
+
+    /// Run the migrations the stored data hasn't seen yet.
+    /// The version is recorded after every migration so a failed one is retried next time
+    /// without repeating the ones before it.
+    pub fn migrate(&self, migrations: &[Migration]) -> Result<(), StorageError> {
+        let version = self.version()?;
+        let latest = migrations.len() as u32;
+        if version > latest {
+            log::warn!(
+                "storage has schema version {} but only {} is known, leaving it alone",
+                version,
+                latest
+            );
+            return Ok(());
+        }
+
+        for (from, migration) in migrations.iter().enumerate().skip(version as usize) {
+            let to = from as u32 + 1;
+            log::info!("migrating storage to schema version {}", to);
+            migration(self.backend.as_ref())?;
+            self.set(&VERSION_KEY, &to)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NAMES: Key<Vec<String>> = Key::new("names");
+
+    #[test]
+    fn stores_typed_values() {
+        let storage = Storage::memory();
+        assert_eq!(storage.get(&NAMES).unwrap(), None);
+
+        let names = vec![String::from("Alice"), String::from("Bob")];
+        storage.set(&NAMES, &names).unwrap();
+        assert_eq!(storage.get(&NAMES).unwrap(), Some(names));
+        assert_eq!(
+            storage.backend.get("hyo.names").unwrap().as_deref(),
+            Some(r#"["Alice","Bob"]"#)
+        );
+
+        storage.remove(&NAMES).unwrap();
+        assert_eq!(storage.get(&NAMES).unwrap(), None);
+    }
+
+    #[test]
+    fn nests_keys() {
+        let key = NAMES.with_suffix("locale/de-DE.ftl");
+        assert_eq!(key.storage_key(), "hyo.names.locale/de-DE.ftl");
+    }
+
+    #[test]
+    fn ignores_unreadable_values() {
+        let storage = Storage::memory();
+        storage.backend.set("hyo.names", "{").unwrap();
+        assert!(matches!(storage.get(&NAMES), Err(StorageError::Decode(_))));
+        assert_eq!(storage.load(&NAMES), None);
+    }
+
+    #[test]
+    fn backs_up_values() {
+        let storage = Storage::memory();
+        storage.back_up(&NAMES).unwrap();
+        assert_eq!(storage.backend.get("hyo.names.backup").unwrap(), None);
+
+        storage.backend.set("hyo.names", "{").unwrap();
+        storage.back_up(&NAMES).unwrap();
+        storage.set(&NAMES, &Vec::new()).unwrap();
+        assert_eq!(
+            storage.backend.get("hyo.names.backup").unwrap().as_deref(),
+            Some("{")
+        );
+    }
+
+    #[test]
+    fn reports_exceeded_quota() {
+        let storage = Storage::new(Memory::with_quota(32));
+        storage.set(&NAMES, &vec![String::from("Alice")]).unwrap();
+
+        let res = storage.set(&NAMES, &vec![String::from("Alice"); 10]);
+        assert!(matches!(res, Err(StorageError::QuotaExceeded)));
+        assert_eq!(storage.load(&NAMES), Some(vec![String::from("Alice")]));
+    }
+
+    fn add_alice(backend: &dyn Backend) -> Result<(), StorageError> {
+        backend.set("hyo.names", r#"["Alice"]"#)
+    }
+
+    fn add_bob(backend: &dyn Backend) -> Result<(), StorageError> {
+        let raw = backend.get("hyo.names")?.unwrap_or_default();
+        backend.set("hyo.names", &raw.replace(']', r#","Bob"]"#))
+    }
+
+    fn fail(_backend: &dyn Backend) -> Result<(), StorageError> {
+        Err(StorageError::QuotaExceeded)
+    }
+
+    #[test]
+    fn runs_pending_migrations() {
+        let storage = Storage::memory();
+        storage.migrate(&[add_alice]).unwrap();
+        assert_eq!(storage.version().unwrap(), 1);
+
+        storage.migrate(&[add_alice, add_bob]).unwrap();
+        assert_eq!(storage.version().unwrap(), 2);
+        assert_eq!(
+            storage.load(&NAMES),
+            Some(vec![String::from("Alice"), String::from("Bob")])
+        );
+    }
+
+    #[test]
+    fn stops_at_failed_migration() {
+        let storage = Storage::memory();
+        assert!(storage.migrate(&[add_alice, fail, add_bob]).is_err());
+        assert_eq!(storage.version().unwrap(), 1);
+        assert_eq!(storage.load(&NAMES), Some(vec![String::from("Alice")]));
+    }
+
+    #[test]
+    fn leaves_newer_schema_alone() {
+        let storage = Storage::memory();
+        storage.set(&VERSION_KEY, &5).unwrap();
+        storage.migrate(&[add_alice]).unwrap();
+        assert_eq!(storage.version().unwrap(), 5);
+        assert_eq!(storage.load(&NAMES), None);
+    }
+}
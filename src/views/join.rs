@@ -0,0 +1,144 @@
+use crate::{
+    components::qr_code::QrCode,
+    locale::{messages, Locale},
+    route::{normalize_room_code, Route},
+};
+use wasm_bindgen_futures::JsFuture;
+use yew::prelude::*;
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct JoinViewProps {
+    pub locale: Locale,
+
+    #[prop_or_default]
+    pub code: Option<String>,
+}
+
+pub struct JoinView {
+    props: JoinViewProps,
+    link: ComponentLink<Self>,
+    code: String,
+}
+
+pub enum JoinViewMsg {
+    CodeInput(String),
+    Join,
+    Back,
+    CopyInviteLink,
+}
+
+impl JoinView {
+    fn invite_url(&self) -> Option<String> {
+        let code = self.props.code.clone()?;
+        Route::Join { code: Some(code) }.to_url()
+    }
+}
+
+impl Component for JoinView {
+    type Message = JoinViewMsg;
+    type Properties = JoinViewProps;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let code = props.code.clone().unwrap_or_default();
+        Self { props, link, code }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            JoinViewMsg::CodeInput(value) => {
+                self.code = normalize_room_code(&value);
+                true
+            }
+            JoinViewMsg::Join => {
+                if self.code.is_empty() {
+                    return false;
+                }
+
+                let route = Route::Join {
+                    code: Some(self.code.clone()),
+                };
+                route.navigate();
+                false
+            }
+            JoinViewMsg::Back => {
+                Route::Start.navigate();
+                false
+            }
+            JoinViewMsg::CopyInviteLink => {
+                if let Some(url) = self.invite_url() {
+                    copy_to_clipboard(url);
+                }
+                false
+            }
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> bool {
+        if self.props != props {
+            if self.props.code != props.code {
+                self.code = props.code.clone().unwrap_or_default();
+            }
+            self.props = props;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn view(&self) -> Html {
+        let locale = &self.props.locale;
+
+        let oninput = self
+            .link
+            .callback(|e: InputData| JoinViewMsg::CodeInput(e.value));
+        let onsubmit = self.link.callback(|e: Event| {
+            e.prevent_default();
+            JoinViewMsg::Join
+        });
+        let onclick_back = self.link.callback(|_| JoinViewMsg::Back);
+
+        let (invite_qr, invite_copy) = if let Some(url) = self.invite_url() {
+            let onclick_copy = self.link.callback(|_| JoinViewMsg::CopyInviteLink);
+            (
+                html! {
+                    <QrCode data=url/>
+                },
+                html! {
+                    <button class="button-row__btn" type="button" onclick=onclick_copy>{ locale.localize(&messages::COPY_INVITE_LINK) }</button>
+                },
+            )
+        } else {
+            (html! {}, html! {})
+        };
+
+        html! {
+            <div class="join-layout">
+                <div class="join-layout__background"/>
+
+                <form class="join-layout__form" onsubmit=onsubmit>
+                    <label class="join-layout__label" for="room-code">{ locale.localize(&messages::ROOM_CODE) }</label>
+                    <input class="join-layout__code" id="room-code" autocomplete="off" maxlength="8" value=self.code.clone() oninput=oninput/>
+                    { invite_qr }
+                    <div class="button-row button-row--center">
+                        <button class="button-row__btn" type="button" onclick=onclick_back>{ locale.localize(&messages::BACK) }</button>
+                        <button class="button-row__btn" type="submit" disabled=self.code.is_empty()>{ locale.localize(&messages::JOIN) }</button>
+                        { invite_copy }
+                    </div>
+                </form>
+            </div>
+        }
+    }
+}
+
+fn copy_to_clipboard(text: String) {
+    let clipboard = match web_sys::window() {
+        Some(window) => window.navigator().clipboard(),
+        None => return,
+    };
+
+    wasm_bindgen_futures::spawn_local(async move {
+        if let Err(e) = JsFuture::from(clipboard.write_text(&text)).await {
+            log::error!("failed to copy `{}` to the clipboard: {:?}", text, e);
+        }
+    });
+}
@@ -1,49 +1,145 @@
-use super::StartView;
-use crate::locale::{FetchFluentError, Locale};
+use super::{JoinView, SettingsView, StartView, StatsView};
+use crate::{
+    fetch::Client,
+    game::Game,
+    locale::{self, FetchFluentError, Locale},
+    profile::Profile,
+    route::Route,
+    settings::Settings,
+    storage::Storage,
+};
 use yew::prelude::*;
 
+#[derive(Clone, PartialEq, Properties)]
+pub struct LoadingViewProps {
+    pub route: Route,
+}
+
 pub struct LoadingView {
+    props: LoadingViewProps,
+    link: ComponentLink<Self>,
+    client: Client,
     locale: Option<Locale>,
+    /// Incremented for every load of the locale so only the latest one is used.
+    locale_generation: u32,
+    settings: Settings,
 }
 
 pub enum LoadingViewMsg {
-    LocaleLoaded(Result<Locale, FetchFluentError>),
+    LoadLocale,
+    SettingsChanged(Settings),
+    /// Result of the load with the given generation.
+    LocaleLoaded(u32, Result<Locale, FetchFluentError>),
 }
 
-impl Component for LoadingView {
-    type Message = LoadingViewMsg;
-    type Properties = ();
+impl LoadingView {
+    fn load_locale(&mut self) {
+        self.locale_generation = self.locale_generation.wrapping_add(1);
+        let generation = self.locale_generation;
+        let link = self.link.clone();
+        let client = self.client.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let locale = Locale::load_for_user(&client).await;
+            link.send_message(LoadingViewMsg::LocaleLoaded(generation, locale));
+        });
+    }
 
-    fn create(_props: Self::Properties, link: ComponentLink<Self>) -> Self {
+    /// Check the cached resources used by the locale for updates and reload it if there are any.
+    fn revalidate_locale(&self) {
+        let link = self.link.clone();
+        let client = self.client.clone();
         wasm_bindgen_futures::spawn_local(async move {
-            let locale = Locale::load_for_user().await;
-            link.send_message(LoadingViewMsg::LocaleLoaded(locale));
+            if client.revalidate().await {
+                link.send_message(LoadingViewMsg::LoadLocale);
+            }
         });
+    }
+}
+
+impl Component for LoadingView {
+    type Message = LoadingViewMsg;
+    type Properties = LoadingViewProps;
 
-        Self { locale: None }
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let mut view = Self {
+            props,
+            link,
+            client: locale::caching_client(),
+            locale: None,
+            locale_generation: 0,
+            settings: Settings::load(&Storage::local()),
+        };
+        view.load_locale();
+        view.settings.apply_to_document();
+        Profile::load(&Storage::local()).apply_to_document();
+        view
     }
 
     fn update(&mut self, msg: Self::Message) -> ShouldRender {
         match msg {
-            LoadingViewMsg::LocaleLoaded(res) => {
-                // TODO error handling
-                self.locale = res.ok();
+            LoadingViewMsg::LoadLocale => {
+                self.load_locale();
+                false
+            }
+            LoadingViewMsg::SettingsChanged(settings) => {
+                settings.apply_to_document();
+                self.settings = settings;
+                true
+            }
+            LoadingViewMsg::LocaleLoaded(generation, _) if generation != self.locale_generation => {
+                // a newer load was started since, its result is the one to use
+                false
+            }
+            LoadingViewMsg::LocaleLoaded(_, Ok(locale)) => {
+                locale.apply_to_document();
+                self.locale = Some(locale);
+                self.revalidate_locale();
                 true
             }
+            LoadingViewMsg::LocaleLoaded(_, Err(err)) => {
+                // TODO error handling
+                log::error!("failed to load locale: {}", err);
+                false
+            }
         }
     }
 
-    fn change(&mut self, _props: Self::Properties) -> bool {
-        false
+    fn change(&mut self, props: Self::Properties) -> bool {
+        if self.props != props {
+            self.props = props;
+            true
+        } else {
+            false
+        }
     }
 
     fn view(&self) -> Html {
-        if let Some(locale) = &self.locale {
-            html! {
-                <StartView locale=locale.clone()/>
-            }
+        let locale = if let Some(locale) = &self.locale {
+            locale.clone()
         } else {
-            html! {}
+            return html! {};
+        };
+
+        match &self.props.route {
+            Route::Start => html! {
+                <StartView locale=locale/>
+            },
+            Route::Join { code } => html! {
+                <JoinView locale=locale code=code.clone()/>
+            },
+            Route::Settings => {
+                let onchange_locale = self.link.callback(|_| LoadingViewMsg::LoadLocale);
+                let onchange_settings = self.link.callback(LoadingViewMsg::SettingsChanged);
+                html! {
+                    <SettingsView locale=locale settings=self.settings.clone() onchange_locale=onchange_locale onchange_settings=onchange_settings/>
+                }
+            }
+            Route::Stats => html! {
+                <StatsView locale=locale/>
+            },
+            Route::Game => html! {
+                <Game locale=locale settings=self.settings.clone()/>
+            },
         }
     }
 }
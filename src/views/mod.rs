@@ -1,5 +1,11 @@
+mod join;
 mod loading;
+mod settings;
 mod start;
+mod stats;
 
+pub use join::*;
 pub use loading::*;
+pub use settings::*;
 pub use start::*;
+pub use stats::*;
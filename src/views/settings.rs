@@ -0,0 +1,509 @@
+use crate::{
+    components::avatar::AvatarImage,
+    fetch::{AbortGuard, Client, FetchError},
+    locale::{
+        self, messages,
+        messages::Message,
+        overlay::{Overlay, OverlayError},
+        pseudo, AvailableLanguage, Locale,
+    },
+    profile::{self, Accent, Avatar, Profile},
+    route::Route,
+    settings::{self, AnimationSpeed, Settings},
+    storage::Storage,
+};
+use unic_langid::LanguageIdentifier;
+use yew::prelude::*;
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct SettingsViewProps {
+    pub locale: Locale,
+
+    /// Emitted after a stored locale preference changed.
+    #[prop_or_else(Callback::noop)]
+    pub onchange_locale: Callback<()>,
+    pub settings: Settings,
+    /// Emitted with the new settings after they've been stored.
+    #[prop_or_else(Callback::noop)]
+    pub onchange_settings: Callback<Settings>,
+}
+
+pub struct SettingsView {
+    props: SettingsViewProps,
+    link: ComponentLink<Self>,
+    languages: Vec<AvailableLanguage>,
+    stored_language: Option<LanguageIdentifier>,
+    bidi_isolation: bool,
+    overlay: Option<Overlay>,
+    overlay_url: String,
+    overlay_failed: bool,
+    profile: Profile,
+    /// Seed of the first avatar to choose from.
+    avatar_base: u32,
+    /// Cancels pending requests when the view is destroyed.
+    abort: AbortGuard,
+}
+
+pub enum SettingsViewMsg {
+    LanguagesLoaded(Result<Vec<AvailableLanguage>, FetchError>),
+    SelectLanguage(Option<LanguageIdentifier>),
+    ToggleBidiIsolation,
+    OverlayFileChange(ChangeData),
+    OverlayUrlInput(String),
+    LoadOverlayUrl,
+    OverlayLoaded(Result<Overlay, OverlayError>),
+    RemoveOverlay,
+    ProfileNameInput(String),
+    SelectAvatar(u32),
+    MoreAvatars,
+    SelectAccent(Accent),
+    ChangeSettings(Settings),
+    Back,
+}
+
+/// Number of avatars to choose from at once.
+const AVATAR_CHOICES: u32 = 8;
+
+impl SettingsView {
+    fn view_language_item(&self, langid: Option<LanguageIdentifier>, name: String) -> Html {
+        let mut class = String::from("language-list__item");
+        if langid == self.stored_language {
+            class.push_str(" language-list__item--active");
+        }
+
+        let lang = langid
+            .as_ref()
+            .map_or_else(String::new, LanguageIdentifier::to_string);
+        let onclick = self
+            .link
+            .callback(move |_| SettingsViewMsg::SelectLanguage(langid.clone()));
+
+        html! {
+            <li>
+                <button class=class lang=lang onclick=onclick>{ name }</button>
+            </li>
+        }
+    }
+
+    fn view_language(&self) -> Html {
+        let locale = &self.props.locale;
+
+        let browser_default = self.view_language_item(
+            None,
+            locale.localize(&messages::LANGUAGE_BROWSER_DEFAULT).into(),
+        );
+        let languages = self
+            .languages
+            .iter()
+            .map(|lang| self.view_language_item(Some(lang.langid.clone()), lang.name.clone()));
+        // the pseudo language is a debugging aid, it can still be enabled with `?pseudo` in release builds
+        let pseudo_language = if cfg!(debug_assertions) {
+            self.view_language_item(
+                Some(pseudo::PSEUDO_LANGUAGE.clone()),
+                pseudo::wrap(&pseudo::transform("Pseudo")),
+            )
+        } else {
+            html! {}
+        };
+
+        let onclick_bidi_isolation = self.link.callback(|_| SettingsViewMsg::ToggleBidiIsolation);
+
+        html! {
+            <>
+                <ul class="language-list">
+                    { browser_default }
+                    { for languages }
+                    { pseudo_language }
+                </ul>
+                <label class="settings-layout__option">
+                    <input type="checkbox" checked=self.bidi_isolation onclick=onclick_bidi_isolation/>
+                    { locale.localize(&messages::BIDI_ISOLATION) }
+                </label>
+            </>
+        }
+    }
+
+    fn view_overlay(&self) -> Html {
+        let locale = &self.props.locale;
+
+        let active = if let Some(overlay) = &self.overlay {
+            let onclick_remove = self.link.callback(|_| SettingsViewMsg::RemoveOverlay);
+            html! {
+                <div class="overlay-form__active">
+                    <span>{ locale.localize(&messages::translation_overlay_active(overlay.name.as_str())) }</span>
+                    <button class="button-row__btn" type="button" onclick=onclick_remove>{ locale.localize(&messages::TRANSLATION_OVERLAY_REMOVE) }</button>
+                </div>
+            }
+        } else {
+            html! {}
+        };
+        let error = if self.overlay_failed {
+            html! {
+                <p class="overlay-form__error">{ locale.localize(&messages::TRANSLATION_OVERLAY_ERROR) }</p>
+            }
+        } else {
+            html! {}
+        };
+
+        let onchange_file = self.link.callback(SettingsViewMsg::OverlayFileChange);
+        let oninput_url = self
+            .link
+            .callback(|e: InputData| SettingsViewMsg::OverlayUrlInput(e.value));
+        let onsubmit = self.link.callback(|e: Event| {
+            e.prevent_default();
+            SettingsViewMsg::LoadOverlayUrl
+        });
+
+        html! {
+            <form class="overlay-form" onsubmit=onsubmit>
+                <p class="overlay-form__description">{ locale.localize(&messages::TRANSLATION_OVERLAY_DESCRIPTION) }</p>
+                { active }
+                <div class="overlay-form__row">
+                    <label class="button-row__btn">
+                        { locale.localize(&messages::TRANSLATION_OVERLAY_FILE) }
+                        <input class="overlay-form__file" type="file" accept=".ftl" onchange=onchange_file/>
+                    </label>
+                </div>
+                <div class="overlay-form__row">
+                    <input class="overlay-form__url" type="url" placeholder=locale.localize(&messages::TRANSLATION_OVERLAY_URL) value=self.overlay_url.clone() oninput=oninput_url/>
+                    <button class="button-row__btn" type="submit" disabled=self.overlay_url.is_empty()>{ locale.localize(&messages::TRANSLATION_OVERLAY_LOAD) }</button>
+                </div>
+                { error }
+            </form>
+        }
+    }
+
+    fn view_profile(&self) -> Html {
+        let locale = &self.props.locale;
+        let profile = &self.profile;
+
+        let oninput_name = self
+            .link
+            .callback(|e: InputData| SettingsViewMsg::ProfileNameInput(e.value));
+
+        let avatars = Avatar::choices(self.avatar_base, AVATAR_CHOICES).map(|avatar| {
+            let seed = avatar.seed();
+            let mut class = String::from("profile-form__choice");
+            if seed == profile.avatar {
+                class.push_str(" profile-form__choice--active");
+            }
+            let onclick = self
+                .link
+                .callback(move |_| SettingsViewMsg::SelectAvatar(seed));
+            html! {
+                <button class=class type="button" onclick=onclick>
+                    <AvatarImage seed=seed/>
+                </button>
+            }
+        });
+        let onclick_more = self.link.callback(|_| SettingsViewMsg::MoreAvatars);
+
+        let accents = Accent::ALL.iter().map(|&accent| {
+            let mut class = String::from("profile-form__choice profile-form__swatch");
+            if accent == profile.accent {
+                class.push_str(" profile-form__choice--active");
+            }
+            let style = format!("background: {}", accent.css_color());
+            let onclick = self
+                .link
+                .callback(move |_| SettingsViewMsg::SelectAccent(accent));
+            html! {
+                <button class=class type="button" style=style title=accent.localize(locale) onclick=onclick/>
+            }
+        });
+
+        html! {
+            <div class="profile-form">
+                <label class="profile-form__row">
+                    { locale.localize(&messages::PROFILE_NAME) }
+                    <input class="profile-form__name" type="text" maxlength=profile::MAX_NAME_LENGTH.to_string() placeholder=locale.localize(&messages::PLAYER_DEFAULT_NAME) value=profile.name.clone() oninput=oninput_name/>
+                </label>
+                <div class="profile-form__row">
+                    <span>{ locale.localize(&messages::PROFILE_AVATAR) }</span>
+                    <div class="profile-form__choices">
+                        { for avatars }
+                        <button class="button-row__btn" type="button" onclick=onclick_more>{ locale.localize(&messages::PROFILE_AVATAR_MORE) }</button>
+                    </div>
+                </div>
+                <div class="profile-form__row">
+                    <span>{ locale.localize(&messages::PROFILE_ACCENT) }</span>
+                    <div class="profile-form__choices">
+                        { for accents }
+                    </div>
+                </div>
+            </div>
+        }
+    }
+
+    /// Checkbox that applies `toggle` to the settings when clicked.
+    fn view_toggle(&self, label: &Message<'_>, checked: bool, toggle: fn(&mut Settings)) -> Html {
+        let mut settings = self.props.settings.clone();
+        toggle(&mut settings);
+        let onclick = self
+            .link
+            .callback(move |_| SettingsViewMsg::ChangeSettings(settings.clone()));
+
+        html! {
+            <label class="settings-layout__option">
+                <input type="checkbox" checked=checked onclick=onclick/>
+                { self.props.locale.localize(label) }
+            </label>
+        }
+    }
+
+    fn view_preferences(&self) -> Html {
+        let locale = &self.props.locale;
+        let settings = &self.props.settings;
+
+        let speed_settings = settings.clone();
+        let onchange_speed = self.link.batch_callback(move |data| match data {
+            ChangeData::Select(select) => AnimationSpeed::from_id(&select.value())
+                .map(|animation_speed| {
+                    SettingsViewMsg::ChangeSettings(Settings {
+                        animation_speed,
+                        ..speed_settings.clone()
+                    })
+                })
+                .into_iter()
+                .collect(),
+            _ => Vec::new(),
+        });
+        let speeds = AnimationSpeed::ALL.iter().map(|&speed| {
+            html! {
+                <option value=speed.id() selected=speed == settings.animation_speed>{ speed.localize(locale) }</option>
+            }
+        });
+
+        let volume_settings = settings.clone();
+        let oninput_volume = self.link.batch_callback(move |e: InputData| {
+            e.value
+                .parse()
+                .ok()
+                .map(|sound_volume: u8| {
+                    SettingsViewMsg::ChangeSettings(Settings {
+                        sound_volume: sound_volume.min(settings::MAX_VOLUME),
+                        ..volume_settings.clone()
+                    })
+                })
+                .into_iter()
+                .collect()
+        });
+
+        html! {
+            <>
+                <label class="settings-layout__option">
+                    { locale.localize(&messages::ANIMATION_SPEED) }
+                    <select class="settings-layout__select" disabled=settings.reduced_motion onchange=onchange_speed>
+                        { for speeds }
+                    </select>
+                </label>
+                { self.view_toggle(&messages::REDUCED_MOTION, settings.reduced_motion, |s| s.reduced_motion = !s.reduced_motion) }
+                { self.view_toggle(&messages::AUTO_SORT_HAND, settings.auto_sort_hand, |s| s.auto_sort_hand = !s.auto_sort_hand) }
+                { self.view_toggle(&messages::CONFIRM_BEFORE_PLAY, settings.confirm_before_play, |s| s.confirm_before_play = !s.confirm_before_play) }
+                { self.view_toggle(&messages::PLAYABLE_CARD_HINTS, settings.playable_card_hints, |s| s.playable_card_hints = !s.playable_card_hints) }
+                <label class="settings-layout__option">
+                    { locale.localize(&messages::SOUND_VOLUME) }
+                    <input type="range" min="0" max=settings::MAX_VOLUME.to_string() value=settings.sound_volume.to_string() oninput=oninput_volume/>
+                </label>
+            </>
+        }
+    }
+
+    /// Store the profile after it changed.
+    fn store_profile(&self) {
+        if let Err(err) = self.profile.store(&Storage::local()) {
+            log::error!("failed to store profile: {}", err);
+        }
+        self.profile.apply_to_document();
+    }
+}
+
+impl Component for SettingsView {
+    type Message = SettingsViewMsg;
+    type Properties = SettingsViewProps;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let abort = AbortGuard::new();
+
+        let languages_link = link.clone();
+        let abort_handle = abort.handle();
+        wasm_bindgen_futures::spawn_local(async move {
+            match locale::load_available_languages(&Client::default(), &abort_handle).await {
+                // the view is gone
+                Err(FetchError::Aborted) => {}
+                languages => {
+                    languages_link.send_message(SettingsViewMsg::LanguagesLoaded(languages))
+                }
+            }
+        });
+
+        let profile = Profile::load(&Storage::local());
+        Self {
+            props,
+            link,
+            languages: Vec::new(),
+            stored_language: locale::get_stored_language(),
+            bidi_isolation: locale::get_stored_bidi_isolation(),
+            overlay: Overlay::get_stored(),
+            overlay_url: String::new(),
+            overlay_failed: false,
+            avatar_base: profile.avatar,
+            profile,
+            abort,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            SettingsViewMsg::LanguagesLoaded(Ok(languages)) => {
+                self.languages = languages;
+                true
+            }
+            SettingsViewMsg::LanguagesLoaded(Err(err)) => {
+                log::error!("failed to load available languages: {}", err);
+                false
+            }
+            SettingsViewMsg::SelectLanguage(langid) => {
+                if self.stored_language == langid {
+                    return false;
+                }
+
+                locale::set_stored_language(langid.as_ref());
+                self.stored_language = langid;
+                self.props.onchange_locale.emit(());
+                true
+            }
+            SettingsViewMsg::ToggleBidiIsolation => {
+                self.bidi_isolation = !self.bidi_isolation;
+                locale::set_stored_bidi_isolation(self.bidi_isolation);
+                self.props.onchange_locale.emit(());
+                true
+            }
+            SettingsViewMsg::OverlayFileChange(ChangeData::Files(files)) => {
+                if let Some(file) = files.get(0) {
+                    let link = self.link.clone();
+                    wasm_bindgen_futures::spawn_local(async move {
+                        let overlay = Overlay::read_file(file).await;
+                        link.send_message(SettingsViewMsg::OverlayLoaded(overlay));
+                    });
+                }
+                false
+            }
+            SettingsViewMsg::OverlayFileChange(_) => false,
+            SettingsViewMsg::OverlayUrlInput(url) => {
+                self.overlay_url = url;
+                true
+            }
+            SettingsViewMsg::LoadOverlayUrl => {
+                if self.overlay_url.is_empty() {
+                    return false;
+                }
+
+                let url = self.overlay_url.clone();
+                let link = self.link.clone();
+                let abort_handle = self.abort.handle();
+                wasm_bindgen_futures::spawn_local(async move {
+                    match Overlay::fetch(&url, &abort_handle).await {
+                        Err(OverlayError::Fetch(FetchError::Aborted)) => {}
+                        overlay => link.send_message(SettingsViewMsg::OverlayLoaded(overlay)),
+                    }
+                });
+                false
+            }
+            SettingsViewMsg::OverlayLoaded(res) => {
+                match res.and_then(|overlay| overlay.store().map(|_| overlay)) {
+                    Ok(overlay) => {
+                        self.overlay = Some(overlay);
+                        self.overlay_url.clear();
+                        self.overlay_failed = false;
+                        self.props.onchange_locale.emit(());
+                    }
+                    Err(err) => {
+                        log::error!("failed to load overlay: {}", err);
+                        self.overlay_failed = true;
+                    }
+                }
+                true
+            }
+            SettingsViewMsg::RemoveOverlay => {
+                Overlay::remove_stored();
+                self.overlay = None;
+                self.overlay_failed = false;
+                self.props.onchange_locale.emit(());
+                true
+            }
+            SettingsViewMsg::ProfileNameInput(name) => {
+                self.profile.set_name(&name);
+                self.store_profile();
+                true
+            }
+            SettingsViewMsg::SelectAvatar(seed) => {
+                self.profile.avatar = seed;
+                self.store_profile();
+                true
+            }
+            SettingsViewMsg::MoreAvatars => {
+                self.avatar_base = self.avatar_base.wrapping_add(AVATAR_CHOICES);
+                true
+            }
+            SettingsViewMsg::SelectAccent(accent) => {
+                self.profile.accent = accent;
+                self.store_profile();
+                true
+            }
+            SettingsViewMsg::ChangeSettings(settings) => {
+                if let Err(err) = settings.store(&Storage::local()) {
+                    log::error!("failed to store settings: {}", err);
+                }
+                self.props.onchange_settings.emit(settings);
+                false
+            }
+            SettingsViewMsg::Back => {
+                Route::Start.navigate();
+                false
+            }
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> bool {
+        if self.props != props {
+            self.props = props;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn view(&self) -> Html {
+        let locale = &self.props.locale;
+
+        let onclick_back = self.link.callback(|_| SettingsViewMsg::Back);
+
+        html! {
+            <div class="settings-layout">
+                <div class="settings-layout__background"/>
+
+                <h1 class="settings-layout__title">{ locale.localize(&messages::SETTINGS) }</h1>
+                <section class="settings-layout__section">
+                    <h2 class="settings-layout__heading">{ locale.localize(&messages::PROFILE) }</h2>
+                    { self.view_profile() }
+                </section>
+                <section class="settings-layout__section">
+                    <h2 class="settings-layout__heading">{ locale.localize(&messages::PREFERENCES) }</h2>
+                    { self.view_preferences() }
+                </section>
+                <section class="settings-layout__section">
+                    <h2 class="settings-layout__heading">{ locale.localize(&messages::LANGUAGE) }</h2>
+                    { self.view_language() }
+                </section>
+                <section class="settings-layout__section">
+                    <h2 class="settings-layout__heading">{ locale.localize(&messages::TRANSLATION_OVERLAY) }</h2>
+                    { self.view_overlay() }
+                </section>
+                <div class="settings-layout__buttons button-row button-row--center">
+                    <button class="button-row__btn" onclick=onclick_back>{ locale.localize(&messages::BACK) }</button>
+                </div>
+            </div>
+        }
+    }
+}
@@ -1,4 +1,9 @@
-use crate::{components::icon::MDIcon, locale::Locale};
+use crate::{
+    components::icon::MDIcon,
+    game,
+    locale::{messages, Locale},
+    route::Route,
+};
 use yew::prelude::*;
 
 #[derive(Clone, PartialEq, Properties)]
@@ -7,6 +12,7 @@ pub struct StartViewProps {
 }
 pub struct StartView {
     props: StartViewProps,
+    has_saved_game: bool,
 }
 
 impl Component for StartView {
@@ -14,7 +20,10 @@ impl Component for StartView {
     type Properties = StartViewProps;
 
     fn create(props: Self::Properties, _link: ComponentLink<Self>) -> Self {
-        Self { props }
+        Self {
+            props,
+            has_saved_game: game::has_saved_game(),
+        }
     }
 
     fn update(&mut self, _msg: Self::Message) -> ShouldRender {
@@ -34,18 +43,39 @@ impl Component for StartView {
         let props = &self.props;
         let locale = &props.locale;
 
+        let onclick_create = Callback::from(|_| {
+            game::clear_saved_game();
+            Route::Game.navigate()
+        });
+        let onclick_join = Callback::from(|_| Route::Join { code: None }.navigate());
+        let onclick_settings = Callback::from(|_| Route::Settings.navigate());
+        let onclick_stats = Callback::from(|_| Route::Stats.navigate());
+
+        let continue_button = if self.has_saved_game {
+            let onclick_continue = Callback::from(|_| Route::Game.navigate());
+            html! {
+                <button class="button-row__btn" onclick=onclick_continue>{ locale.localize(&messages::CONTINUE_GAME) }</button>
+            }
+        } else {
+            html! {}
+        };
+
         html! {
             <div class="start-layout">
                 <div class="start-layout__background"/>
 
-                <h1 class="start-layout__title">{ locale.localize("title", None) }</h1>
+                <h1 class="start-layout__title">{ locale.localize(&messages::TITLE) }</h1>
                 <div class="start-layout__buttons button-row button-row--center">
-                    <button class="button-row__btn">{ locale.localize("create-game", None) }</button>
-                    <button class="button-row__btn">{ locale.localize("join-game", None) }</button>
+                    { continue_button }
+                    <button class="button-row__btn" onclick=onclick_create>{ locale.localize(&messages::CREATE_GAME) }</button>
+                    <button class="button-row__btn" onclick=onclick_join>{ locale.localize(&messages::JOIN_GAME) }</button>
                 </div>
-                <div class="start-layout__settings">
+                <button class="start-layout__stats" title=locale.localize(&messages::STATS) onclick=onclick_stats>
+                    <MDIcon icon="leaderboard"/>
+                </button>
+                <button class="start-layout__settings" title=locale.localize(&messages::SETTINGS) onclick=onclick_settings>
                     <MDIcon icon="settings"/>
-                </div>
+                </button>
             </div>
         }
     }
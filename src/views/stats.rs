@@ -0,0 +1,279 @@
+use crate::{
+    locale::{messages, messages::Message, Locale},
+    profile::{
+        achievements::ACHIEVEMENTS,
+        stats::{MatchSummary, Stats},
+        Profile,
+    },
+    route::Route,
+    storage::{Storage, StorageError},
+};
+use thiserror::Error;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use yew::prelude::*;
+
+/// File name suggested when exporting the statistics.
+const EXPORT_FILE_NAME: &str = "hyo-stats.json";
+
+#[derive(Debug, Error)]
+pub enum StatsImportError {
+    #[error("failed to read file: {0:?}")]
+    Read(JsValue),
+    #[error("failed to decode stats: {0}")]
+    Decode(#[from] serde_json::Error),
+    #[error("failed to store stats: {0}")]
+    Storage(#[from] StorageError),
+}
+
+async fn read_stats(file: web_sys::File) -> Result<Stats, StatsImportError> {
+    let text = JsFuture::from(file.text())
+        .await
+        .map_err(StatsImportError::Read)?;
+    Ok(Stats::from_json(&text.as_string().unwrap_or_default())?)
+}
+
+/// Let the browser download a JSON file with the given contents.
+fn download_json(name: &str, contents: &str) -> Result<(), JsValue> {
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or_else(|| JsValue::from_str("no document"))?;
+
+    let parts = js_sys::Array::of1(&JsValue::from_str(contents));
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type("application/json");
+    let blob = web_sys::Blob::new_with_str_sequence_and_options(&parts, &options)?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob)?;
+
+    let anchor: web_sys::HtmlAnchorElement = document.create_element("a")?.unchecked_into();
+    anchor.set_href(&url);
+    anchor.set_download(name);
+    anchor.click();
+    web_sys::Url::revoke_object_url(&url)
+}
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct StatsViewProps {
+    pub locale: Locale,
+}
+
+pub struct StatsView {
+    props: StatsViewProps,
+    link: ComponentLink<Self>,
+    stats: Stats,
+    profile: Profile,
+    import_failed: bool,
+}
+
+pub enum StatsViewMsg {
+    Export,
+    ImportFileChange(ChangeData),
+    Imported(Result<Stats, StatsImportError>),
+    Back,
+}
+
+impl StatsView {
+    fn view_value(&self, label: Message<'_>, value: String) -> Html {
+        html! {
+            <>
+                <dt class="stats-list__label">{ self.props.locale.localize(&label) }</dt>
+                <dd class="stats-list__value">{ value }</dd>
+            </>
+        }
+    }
+
+    fn view_summary(&self) -> Html {
+        let locale = &self.props.locale;
+        let stats = &self.stats;
+
+        let number = |value: f64| locale.localize(&messages::stats_value(value)).into_owned();
+        let none = || locale.localize(&messages::STATS_VALUE_NONE).into_owned();
+        let average_position = stats.average_position().map_or_else(none, number);
+        let most_played_card = stats.most_played_card().map_or_else(none, |card| {
+            locale.localize(&messages::stats_card(card)).into_owned()
+        });
+
+        html! {
+            <dl class="stats-list">
+                { self.view_value(messages::STATS_GAMES_PLAYED, number(stats.games_played.into())) }
+                { self.view_value(messages::STATS_GAMES_WON, number(stats.games_won.into())) }
+                { self.view_value(messages::STATS_AVERAGE_POSITION, average_position) }
+                { self.view_value(messages::STATS_CARDS_DRAWN, number(stats.cards_drawn.into())) }
+                { self.view_value(messages::STATS_LONGEST_STREAK, number(stats.longest_streak.into())) }
+                { self.view_value(messages::STATS_MOST_PLAYED_CARD, most_played_card) }
+                { self.view_value(messages::STATS_MISSED_HYO_CALLS, number(stats.missed_hyo_calls.into())) }
+            </dl>
+        }
+    }
+
+    fn view_achievements(&self) -> Html {
+        let locale = &self.props.locale;
+
+        let achievements = ACHIEVEMENTS.iter().map(|achievement| {
+            let mut class = String::from("achievement-list__item");
+            if self.profile.has_unlocked(achievement) {
+                class.push_str(" achievement-list__item--unlocked");
+            }
+            html! {
+                <li class=class>
+                    <strong>{ locale.localize(&achievement.name) }</strong>
+                    <span>{ locale.localize(&achievement.description) }</span>
+                </li>
+            }
+        });
+
+        html! {
+            <ul class="achievement-list">
+                { for achievements }
+            </ul>
+        }
+    }
+
+    fn view_match(&self, summary: &MatchSummary) -> Html {
+        let locale = &self.props.locale;
+
+        let date = js_sys::Date::new(&JsValue::from_f64(summary.finished_at))
+            .to_locale_date_string(&locale.language().to_string(), &JsValue::UNDEFINED);
+        let mut class = String::from("match-list__item");
+        if summary.is_win() {
+            class.push_str(" match-list__item--won");
+        }
+
+        html! {
+            <li class=class>
+                <span>{ String::from(date) }</span>
+                <span>{ locale.localize(&messages::stats_match_position(summary.position, summary.players)) }</span>
+                <span>{ locale.localize(&messages::stats_match_cards_drawn(summary.cards_drawn)) }</span>
+            </li>
+        }
+    }
+}
+
+impl Component for StatsView {
+    type Message = StatsViewMsg;
+    type Properties = StatsViewProps;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        Self {
+            props,
+            link,
+            stats: Stats::load(&Storage::local()),
+            profile: Profile::load(&Storage::local()),
+            import_failed: false,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            StatsViewMsg::Export => {
+                let res = self
+                    .stats
+                    .to_json()
+                    .map_err(|err| JsValue::from_str(&err.to_string()))
+                    .and_then(|json| download_json(EXPORT_FILE_NAME, &json));
+                if let Err(e) = res {
+                    log::error!("failed to export stats: {:?}", e);
+                }
+                false
+            }
+            StatsViewMsg::ImportFileChange(ChangeData::Files(files)) => {
+                if let Some(file) = files.get(0) {
+                    let link = self.link.clone();
+                    wasm_bindgen_futures::spawn_local(async move {
+                        let stats = read_stats(file).await;
+                        link.send_message(StatsViewMsg::Imported(stats));
+                    });
+                }
+                false
+            }
+            StatsViewMsg::ImportFileChange(_) => false,
+            StatsViewMsg::Imported(res) => {
+                let res = res.and_then(|stats| {
+                    stats.store(&Storage::local())?;
+                    Ok(stats)
+                });
+                match res {
+                    Ok(stats) => {
+                        self.stats = stats;
+                        self.import_failed = false;
+                    }
+                    Err(err) => {
+                        log::error!("failed to import stats: {}", err);
+                        self.import_failed = true;
+                    }
+                }
+                true
+            }
+            StatsViewMsg::Back => {
+                Route::Start.navigate();
+                false
+            }
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> bool {
+        if self.props != props {
+            self.props = props;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn view(&self) -> Html {
+        let locale = &self.props.locale;
+        let stats = &self.stats;
+
+        let history = if stats.history.is_empty() {
+            html! {
+                <p>{ locale.localize(&messages::STATS_HISTORY_EMPTY) }</p>
+            }
+        } else {
+            html! {
+                <ol class="match-list">
+                    { for stats.history.iter().map(|summary| self.view_match(summary)) }
+                </ol>
+            }
+        };
+
+        let error = if self.import_failed {
+            html! {
+                <p class="stats-layout__error">{ locale.localize(&messages::STATS_IMPORT_ERROR) }</p>
+            }
+        } else {
+            html! {}
+        };
+
+        let onclick_export = self.link.callback(|_| StatsViewMsg::Export);
+        let onchange_import = self.link.callback(StatsViewMsg::ImportFileChange);
+        let onclick_back = self.link.callback(|_| StatsViewMsg::Back);
+
+        html! {
+            <div class="stats-layout">
+                <div class="stats-layout__background"/>
+
+                <h1 class="stats-layout__title">{ locale.localize(&messages::STATS) }</h1>
+                <section class="stats-layout__section">
+                    { self.view_summary() }
+                </section>
+                <section class="stats-layout__section">
+                    <h2 class="stats-layout__heading">{ locale.localize(&messages::ACHIEVEMENTS) }</h2>
+                    { self.view_achievements() }
+                </section>
+                <section class="stats-layout__section">
+                    <h2 class="stats-layout__heading">{ locale.localize(&messages::STATS_HISTORY) }</h2>
+                    { history }
+                </section>
+                { error }
+                <div class="stats-layout__buttons button-row button-row--center">
+                    <button class="button-row__btn" onclick=onclick_export>{ locale.localize(&messages::STATS_EXPORT) }</button>
+                    <label class="button-row__btn">
+                        { locale.localize(&messages::STATS_IMPORT) }
+                        <input class="stats-layout__file" type="file" accept=".json,application/json" onchange=onchange_import/>
+                    </label>
+                    <button class="button-row__btn" onclick=onclick_back>{ locale.localize(&messages::BACK) }</button>
+                </div>
+            </div>
+        }
+    }
+}
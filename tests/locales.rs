@@ -0,0 +1,143 @@
+//! Consistency checks for the translations in `site/locale`.
+//!
+//! Every locale is compared against the fallback locale which defines the messages the
+//! code can use.
+
+#[path = "../build/message_refs.rs"]
+mod message_refs;
+
+use fluent_syntax::parser;
+use message_refs::{collect_messages, line_column, resolve_variables};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::Path,
+};
+
+const LOCALE_DIR: &str = "site/locale";
+const FALLBACK_LOCALE: &str = "en-GB";
+
+/// Variables used by each message, including the ones of referenced messages.
+type Messages = BTreeMap<String, BTreeSet<String>>;
+
+struct Locale {
+    id: String,
+    messages: Messages,
+    /// Syntax errors formatted as `file:line:column: kind`.
+    errors: Vec<String>,
+}
+
+fn load_locale(path: &Path) -> Locale {
+    let id = path.file_stem().unwrap().to_str().unwrap().to_owned();
+    let source = fs::read_to_string(path).unwrap();
+
+    let (resource, parse_errors) = match parser::parse(&source) {
+        Ok(resource) => (resource, Vec::new()),
+        Err((resource, errors)) => (resource, errors),
+    };
+    let errors = parse_errors
+        .iter()
+        .map(|err| {
+            let (line, column) = line_column(&source, err.pos.0);
+            format!("{}:{}:{}: {:?}", path.display(), line, column, err.kind)
+        })
+        .collect();
+
+    let refs = collect_messages(&resource);
+    let messages = refs
+        .keys()
+        .map(|&id| {
+            let variables = resolve_variables(id, &refs)
+                .into_iter()
+                .map(ToOwned::to_owned)
+                .collect();
+            (id.to_owned(), variables)
+        })
+        .collect();
+
+    Locale {
+        id,
+        messages,
+        errors,
+    }
+}
+
+fn load_locales() -> (Locale, Vec<Locale>) {
+    let mut paths: Vec<_> = fs::read_dir(LOCALE_DIR)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "ftl"))
+        .collect();
+    paths.sort();
+
+    let (fallback, others): (Vec<_>, Vec<_>) = paths
+        .iter()
+        .map(|path| load_locale(path))
+        .partition(|locale| locale.id == FALLBACK_LOCALE);
+    let fallback = fallback
+        .into_iter()
+        .next()
+        .expect("fallback locale is missing");
+    (fallback, others)
+}
+
+fn assert_no_problems(problems: Vec<String>) {
+    assert!(
+        problems.is_empty(),
+        "found {} problem(s):\n{}",
+        problems.len(),
+        problems.join("\n")
+    );
+}
+
+#[test]
+fn locales_have_no_syntax_errors() {
+    let (fallback, others) = load_locales();
+    let problems = std::iter::once(&fallback)
+        .chain(&others)
+        .flat_map(|locale| locale.errors.clone())
+        .collect();
+    assert_no_problems(problems);
+}
+
+#[test]
+fn locales_have_the_same_messages() {
+    let (fallback, others) = load_locales();
+
+    let mut problems = Vec::new();
+    for locale in &others {
+        for id in fallback.messages.keys() {
+            if !locale.messages.contains_key(id) {
+                problems.push(format!("{}: missing message `{}`", locale.id, id));
+            }
+        }
+        for id in locale.messages.keys() {
+            if !fallback.messages.contains_key(id) {
+                problems.push(format!("{}: extra message `{}`", locale.id, id));
+            }
+        }
+    }
+    assert_no_problems(problems);
+}
+
+#[test]
+fn locales_use_known_variables() {
+    let (fallback, others) = load_locales();
+
+    let mut problems = Vec::new();
+    for locale in &others {
+        for (id, variables) in &locale.messages {
+            let expected = match fallback.messages.get(id) {
+                Some(expected) => expected,
+                None => continue,
+            };
+            for var in variables.difference(expected) {
+                problems.push(format!(
+                    "{}: message `{}` uses variable `${}` which isn't passed by {}",
+                    locale.id, id, var, FALLBACK_LOCALE
+                ));
+            }
+        }
+    }
+    assert_no_problems(problems);
+}